@@ -1,8 +1,8 @@
 use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
-use rand::thread_rng;
+use rand::rng;
 use rand::seq::SliceRandom;
 
-use skewheap::SkewHeap;
+use skewheap::{MergeStrategy, SkewHeap};
 
 fn fill_drain(c: &mut Criterion) {
     let mut group = c.benchmark_group("fill then drain skewheap of size");
@@ -12,10 +12,14 @@ fn fill_drain(c: &mut Criterion) {
         group.throughput(Throughput::Elements(*count as u64));
         group.bench_with_input(BenchmarkId::from_parameter(*count), &count, |b, &count| {
             let mut items: Vec<u32> = (0..*count).collect();
-            items.shuffle(&mut thread_rng());
+            items.shuffle(&mut rng());
+
+            // reuse a single heap across iterations so that its nodes are recycled rather than
+            // allocated anew on every pass
+            let mut s = SkewHeap::builder().recycling().build().unwrap();
 
             b.iter(|| {
-                let mut s = SkewHeap::new();
+                s.reset_for_reuse();
 
                 for n in &items{
                     s.put(n);
@@ -31,5 +35,133 @@ fn fill_drain(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, fill_drain);
+fn heapify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build skewheap of 100k shuffled items");
+    let count = 100_000;
+
+    let mut items: Vec<u32> = (0..count).collect();
+    items.shuffle(&mut rng());
+
+    group.throughput(Throughput::Elements(count as u64));
+
+    group.bench_function("put", |b| {
+        b.iter(|| {
+            let mut s = SkewHeap::new();
+
+            for n in &items {
+                s.put(*n);
+            }
+
+            s
+        })
+    });
+
+    group.bench_function("heapify", |b| {
+        b.iter(|| SkewHeap::heapify(items.clone()))
+    });
+
+    group.bench_function("from_unsorted", |b| {
+        b.iter(|| SkewHeap::from_unsorted(items.clone()))
+    });
+
+    group.finish();
+}
+
+fn monotonic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill skewheap with 100k increasing items");
+    let count = 100_000;
+
+    group.throughput(Throughput::Elements(count as u64));
+
+    group.bench_function("put", |b| {
+        b.iter(|| {
+            let mut s = SkewHeap::new();
+
+            for n in 0..count {
+                s.put(n);
+            }
+
+            s
+        })
+    });
+
+    group.bench_function("put_max_hint", |b| {
+        b.iter(|| {
+            let mut s = SkewHeap::new();
+
+            for n in 0..count {
+                s.put_max_hint(n);
+            }
+
+            s
+        })
+    });
+
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert batch of 100k shuffled items into skewheap");
+    let count = 100_000;
+
+    let mut items: Vec<u32> = (0..count).collect();
+    items.shuffle(&mut rng());
+
+    let mut base = SkewHeap::new();
+    for n in 0..1000 {
+        base.put(n * 100);
+    }
+
+    group.throughput(Throughput::Elements(count as u64));
+
+    group.bench_function("extend", |b| {
+        b.iter(|| {
+            let mut s = base.clone();
+            s.extend(items.iter().copied());
+            s
+        })
+    });
+
+    group.bench_function("merge_iter", |b| {
+        b.iter(|| {
+            let mut s = base.clone();
+            s.merge_iter(items.iter().copied());
+            s
+        })
+    });
+
+    group.finish();
+}
+
+fn merge_strategy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill then drain skewheap of 10k items by merge strategy");
+    let count = 10_000;
+
+    let mut items: Vec<u32> = (0..count).collect();
+    items.shuffle(&mut rng());
+
+    group.throughput(Throughput::Elements(count as u64));
+
+    for strategy in [MergeStrategy::Recursive, MergeStrategy::Iterative].iter() {
+        let id = BenchmarkId::from_parameter(format!("{:?}", strategy));
+
+        group.bench_with_input(id, strategy, |b, &strategy| {
+            b.iter(|| {
+                let mut s = SkewHeap::builder().merge_strategy(strategy).build().unwrap();
+
+                for n in &items {
+                    s.put(*n);
+                }
+
+                while !s.is_empty() {
+                    s.take();
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, fill_drain, heapify, monotonic, batch, merge_strategy);
 criterion_main!(benches);
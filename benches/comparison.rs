@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId, Throughput};
-use rand::thread_rng;
+use rand::rng;
 use rand::seq::SliceRandom;
 use std::collections::BinaryHeap;
 
@@ -14,7 +14,7 @@ fn skew_heap(c: &mut Criterion) {
         group.throughput(Throughput::Elements(count as u64));
         group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
             let mut items: Vec<u32> = (0..count).collect();
-            items.shuffle(&mut thread_rng());
+            items.shuffle(&mut rng());
 
             b.iter(|| {
                 let mut s = SkewHeap::new();
@@ -42,7 +42,7 @@ fn binary_heap(c: &mut Criterion) {
         group.throughput(Throughput::Elements(count as u64));
         group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
             let mut items: Vec<u32> = (0..count).collect();
-            items.shuffle(&mut thread_rng());
+            items.shuffle(&mut rng());
 
             b.iter(|| {
                 let mut b = BinaryHeap::new();
@@ -0,0 +1,237 @@
+//! A bounded heap with asynchronous backpressure, enabled by the async feature
+
+use std::future::poll_fn;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Poll, Waker};
+
+use crate::{BuildError, Item, SkewHeap};
+
+
+#[derive(Debug)]
+struct State<T> {
+    heap:    SkewHeap<T>,
+    putters: Vec<Waker>,
+    takers:  Vec<Waker>,
+}
+
+/// A bounded priority channel for async tasks. put() waits while the heap is at capacity and
+/// take() waits while it is empty, each resuming once the other side makes room or supplies an
+/// item. Clones share the same underlying heap, so producers and consumers may each hold one.
+///
+/// The waiting is built on std's Waker alone, so AsyncSkewHeap works under tokio or any other
+/// executor.
+#[derive(Debug)]
+pub struct AsyncSkewHeap<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T: Item> AsyncSkewHeap<T> {
+    /// Returns a new AsyncSkewHeap holding at most capacity items
+    pub fn new(capacity: usize) -> Result<Self, BuildError> {
+        let heap = SkewHeap::builder().capacity(capacity).build()?;
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(State {
+                heap,
+                putters: vec![],
+                takers:  vec![],
+            })),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().expect("AsyncSkewHeap lock poisoned")
+    }
+
+    /// Returns the number of items currently in the heap
+    pub fn size(&self) -> usize {
+        self.lock().heap.size()
+    }
+
+    /// Returns true if there are no items currently in the heap
+    pub fn is_empty(&self) -> bool {
+        self.lock().heap.is_empty()
+    }
+
+    /// Inserts an item into the heap, first waiting for room if the heap is at capacity, and
+    /// returns the new size
+    pub async fn put(&self, item: T) -> usize {
+        let mut item = Some(item);
+
+        poll_fn(|cx| {
+            let mut state = self.lock();
+
+            if state.heap.is_full() {
+                register(&mut state.putters, cx.waker());
+                return Poll::Pending
+            }
+
+            let size = state.heap.put(item.take().expect("put polled after completion"));
+            state.takers.drain(..).for_each(Waker::wake);
+
+            Poll::Ready(size)
+        }).await
+    }
+
+    /// Removes and returns the top item from the heap, first waiting for one to be put if the
+    /// heap is empty
+    pub async fn take(&self) -> T {
+        poll_fn(|cx| {
+            let mut state = self.lock();
+
+            match state.heap.take() {
+                Some(item) => {
+                    state.putters.drain(..).for_each(Waker::wake);
+                    Poll::Ready(item)
+                },
+                None => {
+                    register(&mut state.takers, cx.waker());
+                    Poll::Pending
+                },
+            }
+        }).await
+    }
+}
+
+/// Adds waker to those waiting, unless one which would wake the same task is already there, so
+/// that a future polled repeatedly while it waits is only woken once
+fn register(waiting: &mut Vec<Waker>, waker: &Waker) {
+    if !waiting.iter().any(|waiting| waiting.will_wake(waker)) {
+        waiting.push(waker.clone());
+    }
+}
+
+impl<T> Clone for AsyncSkewHeap<T> {
+    /// Returns a new handle to the same underlying heap
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncSkewHeap;
+    use crate::BuildError;
+
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+    use std::time::Duration;
+
+    /// Runs a future to completion on the current thread, parking while it is pending
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct Unparker(Thread);
+
+        impl Wake for Unparker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(Unparker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending       => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        assert_eq!(AsyncSkewHeap::<u32>::new(0).err(), Some(BuildError::ZeroCapacity));
+    }
+
+    #[test]
+    fn test_repoll_registers_once() {
+        let heap = AsyncSkewHeap::<u32>::new(1).unwrap();
+        struct Ignore;
+
+        impl Wake for Ignore {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(Ignore));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut take = pin!(heap.take());
+        for _ in 0..10 {
+            assert!(take.as_mut().poll(&mut cx).is_pending(), "take waits while the heap is empty");
+        }
+        assert_eq!(heap.lock().takers.len(), 1, "a repolled take is registered once");
+
+        block_on(heap.put(1));
+        let mut put = pin!(heap.put(2));
+        for _ in 0..10 {
+            assert!(put.as_mut().poll(&mut cx).is_pending(), "put waits while the heap is full");
+        }
+        assert_eq!(heap.lock().putters.len(), 1, "a repolled put is registered once");
+    }
+
+    #[test]
+    fn test_backpressure() {
+        let heap = AsyncSkewHeap::new(3).unwrap();
+
+        for n in [3, 1, 2] {
+            block_on(heap.put(n));
+        }
+
+        // the heap is full, so this put waits until the consumer makes room
+        let producer = {
+            let heap = heap.clone();
+            thread::spawn(move || block_on(heap.put(0)))
+        };
+
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(heap.size(), 3, "put waits while heap is at capacity");
+
+        assert_eq!(block_on(heap.take()), 1, "take returns top item");
+        assert_eq!(producer.join().unwrap(), 3, "waiting put completes once there is room");
+
+        assert_eq!(block_on(heap.take()), 0, "item from waiting put is taken by priority");
+        assert_eq!(block_on(heap.take()), 2);
+        assert_eq!(block_on(heap.take()), 3);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_producer_consumer() {
+        let heap = AsyncSkewHeap::new(8).unwrap();
+        let count = 1000;
+
+        let consumer = {
+            let heap = heap.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    let mut sum = 0;
+                    for _ in 0..count {
+                        sum += heap.take().await;
+                    }
+                    sum
+                })
+            })
+        };
+
+        let producer = {
+            let heap = heap.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    for n in 0..count {
+                        heap.put(n).await;
+                    }
+                })
+            })
+        };
+
+        producer.join().unwrap();
+        assert_eq!(consumer.join().unwrap(), (0..count).sum::<u64>(), "every item is exchanged");
+        assert!(heap.is_empty());
+    }
+}
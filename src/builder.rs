@@ -0,0 +1,272 @@
+//! Configures a SkewHeap before construction
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::{Item, Order, SkewHeap};
+
+
+/// Errors returned by SkewHeapBuilder::build when the requested configuration is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// A bounded heap must be able to hold at least one item
+    ZeroCapacity,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::ZeroCapacity => write!(f, "capacity must be greater than zero"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+
+/// How a heap responds when its item count would exceed usize::MAX
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Panic with a clear message (the default)
+    Panic,
+    /// Stop counting at usize::MAX. Once saturated, size() no longer reflects the exact number of
+    /// items.
+    Saturate,
+}
+
+
+/// How a heap merges its trees. Both strategies build identical trees, and so take items in the
+/// same order; they differ only in how the work is carried out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Recurse down the right spines of the trees being merged (the default)
+    Recursive,
+    /// Walk down the right spines in a loop, using no stack however long they grow
+    Iterative,
+}
+
+
+/// Builds a SkewHeap with a chosen order and an optional capacity bound. Unless otherwise
+/// configured, the resulting heap is an unbounded min heap, identical to SkewHeap::new().
+#[derive(Debug, Clone)]
+pub struct SkewHeapBuilder<T> {
+    order:     Order,
+    capacity:  Option<usize>,
+    overflow:  Overflow,
+    sequenced: bool,
+    strategy:  MergeStrategy,
+    recycle:   bool,
+    interner:  Option<fn(&T) -> T>,
+    item:      PhantomData<T>,
+}
+
+impl<T: Item> SkewHeapBuilder<T> {
+    /// Returns a new SkewHeapBuilder
+    pub fn new() -> Self {
+        Self {
+            order:     Order::Min,
+            capacity:  None,
+            overflow:  Overflow::Panic,
+            sequenced: false,
+            strategy:  MergeStrategy::Recursive,
+            recycle:   false,
+            interner:  None,
+            item:      PhantomData,
+        }
+    }
+
+    /// Sets the order in which items will be taken from the heap
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Bounds the heap to hold no more than capacity items
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Sets how the heap responds when its item count would overflow usize
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Breaks ties between equal items by insertion order, so that they are taken first in,
+    /// first out. This costs an extra comparison whenever equal items meet during a merge.
+    pub fn sequenced(mut self) -> Self {
+        self.sequenced = true;
+        self
+    }
+
+    /// Keeps the nodes freed by take() for reuse by later calls to put(), rather than returning
+    /// them to the allocator. The heap then holds on to as many nodes as it has ever held at once
+    /// until it is dropped or shrink_pool_to() is called.
+    pub fn recycling(mut self) -> Self {
+        self.recycle = true;
+        self
+    }
+
+    /// Sets how the heap merges its trees
+    pub fn merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Validates the configuration and returns the configured SkewHeap
+    pub fn build(self) -> Result<SkewHeap<T>, BuildError> {
+        if self.capacity == Some(0) {
+            return Err(BuildError::ZeroCapacity)
+        }
+
+        let mut heap = SkewHeap::new();
+        heap.order = self.order;
+        heap.capacity = self.capacity;
+        heap.overflow = self.overflow;
+        heap.sequenced = self.sequenced;
+        heap.strategy = self.strategy;
+        heap.recycle = self.recycle;
+        heap.interner = self.interner;
+
+        Ok(heap)
+    }
+}
+
+impl<T: Item + Clone> SkewHeapBuilder<T> {
+    /// Interns duplicate items: when an item being put is equal to one already found along its
+    /// insertion path, that node's count is incremented rather than allocating a new node, and
+    /// taking from the node hands out clones of its item until its count is exhausted. This saves
+    /// memory in heaps with many repeated items. size() still reports every item.
+    pub fn interning(mut self) -> Self {
+        self.interner = Some(T::clone);
+        self
+    }
+}
+
+impl<T: Item> Default for SkewHeapBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildError, Overflow};
+    use crate::{Order, SkewHeap};
+
+    #[test]
+    fn test_defaults() {
+        let skew: SkewHeap<u32> = SkewHeap::builder().build().unwrap();
+        assert_eq!(skew.order(), Order::Min, "default order is min");
+        assert_eq!(skew.capacity(), None, "default heap is unbounded");
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let result: Result<SkewHeap<u32>, _> = SkewHeap::builder().capacity(0).build();
+        assert_eq!(result.err(), Some(BuildError::ZeroCapacity), "zero capacity is rejected");
+    }
+
+    #[test]
+    fn test_bounded_max_heap() {
+        let mut skew = SkewHeap::builder()
+            .order(Order::Max)
+            .capacity(3)
+            .build()
+            .unwrap();
+
+        assert_eq!(skew.order(), Order::Max);
+        assert_eq!(skew.capacity(), Some(3));
+
+        assert_eq!(skew.try_put(5), Ok(1), "try_put returns new size");
+        assert_eq!(skew.try_put(9), Ok(2), "try_put returns new size");
+        assert_eq!(skew.try_put(2), Ok(3), "try_put returns new size");
+        assert!(skew.is_full(), "is_full once capacity is reached");
+        assert_eq!(skew.try_put(7), Err(7), "try_put returns the item when full");
+        assert_eq!(skew.size(), 3, "rejected item is not counted");
+
+        assert_eq!(skew.take(), Some(9), "max heap returns largest first");
+        assert!(!skew.is_full(), "not full after take");
+        assert_eq!(skew.try_put(7), Ok(3), "try_put succeeds once there is room");
+
+        assert_eq!(skew.take(), Some(7));
+        assert_eq!(skew.take(), Some(5));
+        assert_eq!(skew.take(), Some(2));
+        assert_eq!(skew.take(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "SkewHeap is at capacity")]
+    fn test_put_when_full() {
+        let mut skew = SkewHeap::builder().capacity(1).build().unwrap();
+        skew.put(1);
+        skew.put(2);
+    }
+
+    #[test]
+    fn test_overflow_saturate() {
+        let mut skew = SkewHeap::builder().overflow(Overflow::Saturate).build().unwrap();
+
+        // test hook: pretend the heap already holds nearly usize::MAX items
+        skew.count = usize::MAX - 1;
+
+        assert_eq!(skew.put(1), usize::MAX, "put reaches usize::MAX");
+        assert_eq!(skew.put(2), usize::MAX, "put saturates rather than wrapping");
+        assert_eq!(skew.saturating_size(), usize::MAX, "saturating_size never wraps");
+    }
+
+    #[test]
+    #[should_panic(expected = "SkewHeap size overflowed usize")]
+    fn test_overflow_panic() {
+        let mut skew = SkewHeap::builder().overflow(Overflow::Panic).build().unwrap();
+
+        // test hook: pretend the heap already holds usize::MAX items
+        skew.count = usize::MAX;
+        skew.put(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "SkewHeap size overflowed usize")]
+    fn test_overflow_adopt() {
+        let mut a = SkewHeap::new();
+        a.put(1);
+        a.count = usize::MAX;
+
+        let mut b = SkewHeap::new();
+        b.put(2);
+        a.adopt(b);
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut skew = SkewHeap::builder().interning().build().unwrap();
+
+        for _ in 0..1000 {
+            skew.put(7);
+        }
+
+        assert_eq!(skew.size(), 1000, "size counts every item");
+        assert_eq!(skew.iter_with_depth().count(), 1, "only one node is allocated");
+
+        skew.put(3);
+        skew.put(9);
+        assert_eq!(skew.size(), 1002);
+
+        let mut cloned = skew.clone();
+        assert_eq!(cloned.take(), Some(3));
+        for _ in 0..1000 {
+            assert_eq!(cloned.take(), Some(7), "clone preserves interned counts");
+        }
+        assert_eq!(cloned.take(), Some(9));
+        assert_eq!(cloned.take(), None);
+
+        let mut items = skew.dismantle();
+        items.sort();
+        assert_eq!(items.len(), 1002, "dismantle returns every interned item");
+        assert_eq!(items[0], 3);
+        assert!(items[1..1001].iter().all(|n| *n == 7));
+        assert_eq!(items[1001], 9);
+    }
+
+}
@@ -0,0 +1,86 @@
+//! Pairs a value with an ordering key computed once, up front
+
+use std::cmp::Ordering;
+
+
+/// An item ordered by a key derived from its value. The key is computed once, when the CachedKey
+/// is created, and stored alongside the value, so an expensive key (such as a hash or a derived
+/// metric) is not recomputed on every comparison made while merging.
+#[derive(Debug, Clone)]
+pub struct CachedKey<K: PartialOrd, V> {
+    key:   K,
+    value: V,
+}
+
+impl<K: PartialOrd, V> CachedKey<K, V> {
+    /// Returns a new CachedKey wrapping value, ordered by the key computed from it
+    pub fn new<F: FnOnce(&V) -> K>(value: V, key: F) -> Self {
+        Self { key: key(&value), value }
+    }
+
+    /// Returns the cached key
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the wrapped value
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Consumes the wrapper, returning the value
+    pub fn into_value(self) -> V {
+        self.value
+    }
+}
+
+impl<K: PartialOrd, V> PartialEq for CachedKey<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: PartialOrd, V> PartialOrd for CachedKey<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CachedKey;
+    use crate::SkewHeap;
+
+    use std::cell::Cell;
+
+    use rand::rng;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_key_computed_once() {
+        let calls = Cell::new(0);
+        let digits = |n: &u32| {
+            calls.set(calls.get() + 1);
+            n.to_string().chars().rev().collect::<String>()
+        };
+
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(CachedKey::new(n, digits));
+        }
+
+        let first = skew.take().unwrap();
+        assert_eq!((first.key().as_str(), *first.value()), ("0", 0), "ordered by key");
+
+        let mut prev = first;
+        while let Some(item) = skew.take() {
+            assert!(prev.key() <= item.key(), "drained in key order");
+            prev = item;
+        }
+
+        assert_eq!(calls.get(), 100, "key is computed once per item, not per comparison");
+    }
+}
@@ -0,0 +1,153 @@
+//! A heap shared between threads
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{Item, SkewHeap};
+
+
+/// A thread-safe SkewHeap for multiple producers and consumers. Each operation locks the shared
+/// heap for its duration. Clones are cheap handles to the same heap, so each thread may hold its
+/// own.
+#[derive(Debug)]
+pub struct ConcurrentSkewHeap<T> {
+    heap: Arc<Mutex<SkewHeap<T>>>,
+}
+
+impl<T: Item> ConcurrentSkewHeap<T> {
+    /// Returns a new, empty ConcurrentSkewHeap
+    pub fn new() -> Self {
+        Self::from_heap(SkewHeap::new())
+    }
+
+    /// Wraps an existing heap (for example, one configured with SkewHeapBuilder) for sharing
+    pub fn from_heap(heap: SkewHeap<T>) -> Self {
+        Self {
+            heap: Arc::new(Mutex::new(heap)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SkewHeap<T>> {
+        self.heap.lock().expect("ConcurrentSkewHeap lock poisoned")
+    }
+
+    /// Returns the number of items in the heap
+    pub fn len(&self) -> usize {
+        self.lock().size()
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Inserts an item into the heap and returns the new size
+    pub fn put(&self, item: T) -> usize {
+        self.lock().put(item)
+    }
+
+    /// Removes and retrieves the top item from the heap
+    pub fn take(&self) -> Option<T> {
+        self.lock().take()
+    }
+
+    /// Locks the heap and passes a reference to the top item (or None if the heap is empty) to f,
+    /// returning f's result. Unlike peek(), this does not clone the item, and the heap cannot
+    /// change while f runs.
+    pub fn peek_with<R, F: FnOnce(Option<&T>) -> R>(&self, f: F) -> R {
+        f(self.lock().peek())
+    }
+}
+
+impl<T: Item + Clone> ConcurrentSkewHeap<T> {
+    /// Retrieves a copy of the top item from the heap without removing it
+    pub fn peek(&self) -> Option<T> {
+        self.lock().peek().cloned()
+    }
+}
+
+impl<T> Clone for ConcurrentSkewHeap<T> {
+    /// Returns a new handle to the same shared heap
+    fn clone(&self) -> Self {
+        Self {
+            heap: Arc::clone(&self.heap),
+        }
+    }
+}
+
+impl<T: Item> Default for ConcurrentSkewHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConcurrentSkewHeap;
+
+    use std::thread;
+
+    #[test]
+    fn test_positive_path() {
+        let heap = ConcurrentSkewHeap::new();
+        assert!(heap.is_empty(), "initially empty");
+        assert_eq!(heap.peek(), None, "peek returns None when empty");
+
+        assert_eq!(heap.put(2), 1, "put returns new size");
+        assert_eq!(heap.put(1), 2, "put returns new size");
+        assert_eq!(heap.peek(), Some(1), "peek returns top item");
+        assert_eq!(heap.len(), 2, "peek does not remove");
+        assert_eq!(heap.take(), Some(1), "take returns top item");
+        assert_eq!(heap.take(), Some(2), "take returns top item");
+        assert_eq!(heap.take(), None, "take returns None when empty");
+    }
+
+    #[test]
+    fn test_threads() {
+        let heap = ConcurrentSkewHeap::new();
+        let (threads, per_thread) = (8, 1000);
+
+        let workers: Vec<_> = (0..threads).map(|t| {
+            let heap = heap.clone();
+
+            thread::spawn(move || {
+                let mut taken = 0;
+
+                for n in 0..per_thread {
+                    heap.put(t * per_thread + n);
+
+                    if n % 2 == 0 && heap.take().is_some() {
+                        taken += 1;
+                    }
+                }
+
+                taken
+            })
+        }).collect();
+
+        let mut taken: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+
+        while heap.take().is_some() {
+            taken += 1;
+        }
+
+        assert_eq!(taken, threads * per_thread, "every inserted item is taken exactly once");
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_peek_with() {
+        // not Clone, so peek() is unavailable
+        #[derive(Debug, PartialEq, PartialOrd)]
+        struct Job(u32, Vec<u8>);
+
+        let heap = ConcurrentSkewHeap::new();
+        assert!(heap.peek_with(|top| top.is_none()), "passes None when empty");
+
+        heap.put(Job(2, vec![0; 1024]));
+        heap.put(Job(1, vec![0; 1024]));
+
+        assert!(heap.peek_with(|top| top.is_some_and(|job| job.0 == 1)), "passes the top item");
+        assert_eq!(heap.peek_with(|top| top.map(|job| job.1.len())), Some(1024), "returns f's result");
+        assert_eq!(heap.len(), 2, "peek_with does not remove");
+    }
+}
@@ -0,0 +1,75 @@
+//! A pair of heaps that trade places between filling and draining
+
+use crate::{Item, SkewHeap};
+
+
+/// Holds two heaps: a back buffer which producers fill and a front buffer which consumers drain.
+/// Calling swap() exchanges their roles in a single step, so that the items gathered so far become
+/// available for draining while filling continues into the other heap.
+#[derive(Debug)]
+pub struct DoubleBufferedHeap<T> {
+    front: SkewHeap<T>,
+    back:  SkewHeap<T>,
+}
+
+impl<T: Item> DoubleBufferedHeap<T> {
+    /// Returns a new DoubleBufferedHeap with two empty buffers
+    pub fn new() -> Self {
+        Self {
+            front: SkewHeap::new(),
+            back:  SkewHeap::new(),
+        }
+    }
+
+    /// Returns the heap into which new items should be put
+    pub fn fill_handle(&mut self) -> &mut SkewHeap<T> {
+        &mut self.back
+    }
+
+    /// Returns the heap from which items should be taken
+    pub fn drain_handle(&mut self) -> &mut SkewHeap<T> {
+        &mut self.front
+    }
+
+    /// Exchanges the roles of the two heaps: the filled back buffer becomes the front buffer to be
+    /// drained, and the previous front buffer (along with anything left in it) becomes the back
+    /// buffer to be filled.
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+}
+
+impl<T: Item> Default for DoubleBufferedHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DoubleBufferedHeap;
+
+    #[test]
+    fn test_fill_swap_drain() {
+        let mut buffers = DoubleBufferedHeap::new();
+
+        for cycle in 0..3 {
+            let base = cycle * 10;
+
+            for n in [base + 3, base + 1, base + 2] {
+                buffers.fill_handle().put(n);
+            }
+
+            assert!(buffers.drain_handle().is_empty(), "front is empty before swap");
+
+            buffers.swap();
+            assert!(buffers.fill_handle().is_empty(), "back is empty after swap");
+
+            let drain = buffers.drain_handle();
+            assert_eq!(drain.take(), Some(base + 1));
+            assert_eq!(drain.take(), Some(base + 2));
+            assert_eq!(drain.take(), Some(base + 3));
+            assert_eq!(drain.take(), None);
+        }
+    }
+}
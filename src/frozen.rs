@@ -0,0 +1,97 @@
+//! An immutable, sorted view of a heap's items
+
+use crate::Order;
+
+
+/// The items of a heap which has stopped changing, held in a single sorted slice, as returned by
+/// SkewHeap::freeze. The top item is found in O(1) and any item in O(log n), but no items may be
+/// added or removed.
+#[derive(Debug, Clone)]
+pub struct FrozenHeap<T> {
+    // always in ascending order; order decides which end is the top
+    items: Box<[T]>,
+    order: Order,
+}
+
+impl<T: Ord> FrozenHeap<T> {
+    /// Sorts items into a new FrozenHeap
+    pub(crate) fn new(mut items: Vec<T>, order: Order) -> Self {
+        items.sort_unstable();
+
+        Self {
+            items: items.into_boxed_slice(),
+            order,
+        }
+    }
+
+    /// Returns the number of items in the FrozenHeap
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items in the FrozenHeap
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Retrieves a reference to the item the heap would have taken first
+    pub fn peek(&self) -> Option<&T> {
+        match self.order {
+            Order::Min => self.items.first(),
+            Order::Max => self.items.last(),
+        }
+    }
+
+    /// Returns true if the FrozenHeap holds an item equal to the one supplied, by binary search
+    pub fn contains(&self, item: &T) -> bool {
+        self.items.binary_search(item).is_ok()
+    }
+
+    /// Returns an iterator over the items in the order the heap would have taken them
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let len = self.items.len();
+        let reversed = self.order == Order::Max;
+
+        (0..len).map(move |i| &self.items[if reversed { len - 1 - i } else { i }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Order, SkewHeap};
+
+    use rand::rng;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_freeze() {
+        let mut items: Vec<u32> = (0..100).map(|n| n * 2).collect();
+        items.shuffle(&mut rng());
+
+        let frozen = SkewHeap::heapify(items).freeze();
+        assert_eq!(frozen.size(), 100, "holds every item");
+        assert_eq!(frozen.peek(), Some(&0), "peek returns the top item");
+
+        assert!(frozen.contains(&0) && frozen.contains(&42) && frozen.contains(&198), "finds held items");
+        assert!(!frozen.contains(&41) && !frozen.contains(&200), "does not find missing items");
+
+        let sorted: Vec<u32> = frozen.iter().copied().collect();
+        assert_eq!(sorted, (0..100).map(|n| n * 2).collect::<Vec<_>>(), "iterates in order");
+    }
+
+    #[test]
+    fn test_freeze_max() {
+        let mut skew = SkewHeap::builder().order(Order::Max).build().unwrap();
+        skew.extend(vec![3, 1, 4, 1, 5]);
+
+        let frozen = skew.freeze();
+        assert_eq!(frozen.peek(), Some(&5), "peek returns the largest item of a max heap");
+        assert!(frozen.contains(&4), "finds held items");
+        assert_eq!(frozen.iter().copied().collect::<Vec<_>>(), vec![5, 4, 3, 1, 1], "iterates in take order");
+
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert!(empty.freeze().peek().is_none(), "empty heap freezes to an empty view");
+    }
+}
@@ -0,0 +1,122 @@
+//! Huffman coding, as a demonstration of the heap in use
+
+use crate::{Prioritized, SkewHeap};
+
+
+/// A prefix code tree. Each leaf holds a symbol, and each interior node the combined weight of the
+/// symbols beneath it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HuffmanTree<S> {
+    Leaf {
+        symbol: S,
+        weight: u64,
+    },
+    Node {
+        weight: u64,
+        left:   Box<HuffmanTree<S>>,
+        right:  Box<HuffmanTree<S>>,
+    },
+}
+
+impl<S: Copy> HuffmanTree<S> {
+    /// Returns the total weight of the symbols in this tree
+    pub fn weight(&self) -> u64 {
+        match self {
+            HuffmanTree::Leaf { weight, .. } => *weight,
+            HuffmanTree::Node { weight, .. } => *weight,
+        }
+    }
+
+    /// Returns each symbol alongside its code, where false selects a left branch and true a right
+    /// branch. A tree holding a single symbol assigns it the empty code.
+    pub fn codes(&self) -> Vec<(S, Vec<bool>)> {
+        let mut codes = vec![];
+        let mut stack = vec![(self, vec![])];
+
+        while let Some((tree, code)) = stack.pop() {
+            match tree {
+                HuffmanTree::Leaf { symbol, .. } => codes.push((*symbol, code)),
+                HuffmanTree::Node { left, right, .. } => {
+                    let mut right_code = code.clone();
+                    right_code.push(true);
+                    stack.push((right, right_code));
+
+                    let mut left_code = code;
+                    left_code.push(false);
+                    stack.push((left, left_code));
+                },
+            }
+        }
+
+        codes
+    }
+}
+
+/// Builds an optimal prefix code tree for the given symbols and their weights by repeatedly
+/// combining the two lightest trees, which a min heap makes cheap. Trees of equal weight are
+/// combined in the order they were built, so the result is deterministic. Panics if symbols is
+/// empty.
+pub fn build_huffman<S: Copy>(symbols: Vec<(S, u64)>) -> HuffmanTree<S> {
+    assert!(!symbols.is_empty(), "build_huffman requires at least one symbol");
+
+    let mut heap = SkewHeap::builder().sequenced().build().expect("unbounded heap is valid");
+
+    for (symbol, weight) in symbols {
+        heap.put(Prioritized::new(weight, HuffmanTree::Leaf { symbol, weight }));
+    }
+
+    loop {
+        let left = heap.take().expect("heap is never left empty").value;
+
+        let right = match heap.take() {
+            Some(right) => right.value,
+            None        => return left,
+        };
+
+        let weight = left.weight() + right.weight();
+
+        heap.put(Prioritized::new(weight, HuffmanTree::Node {
+            weight,
+            left:  Box::new(left),
+            right: Box::new(right),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_huffman, HuffmanTree};
+
+    #[test]
+    fn test_weighted_code_length() {
+        let symbols = vec![('a', 45), ('b', 13), ('c', 12), ('d', 16), ('e', 9), ('f', 5)];
+        let tree = build_huffman(symbols.clone());
+
+        assert_eq!(tree.weight(), 100, "root holds the total weight");
+
+        let codes = tree.codes();
+        assert_eq!(codes.len(), symbols.len(), "every symbol is assigned a code");
+
+        let length: u64 = codes.iter()
+            .map(|(symbol, code)| {
+                let (_, weight) = symbols.iter().find(|(s, _)| s == symbol).unwrap();
+                weight * code.len() as u64
+            })
+            .sum();
+
+        assert_eq!(length, 224, "total weighted code length is optimal");
+
+        for (i, (_, a)) in codes.iter().enumerate() {
+            for (_, b) in &codes[i + 1..] {
+                assert!(!b.starts_with(a) && !a.starts_with(b), "no code is a prefix of another");
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_symbol() {
+        let tree = build_huffman(vec![('a', 7)]);
+        assert_eq!(tree, HuffmanTree::Leaf { symbol: 'a', weight: 7 });
+        assert_eq!(tree.codes(), vec![('a', vec![])], "lone symbol has the empty code");
+    }
+}
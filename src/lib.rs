@@ -1,29 +1,142 @@
 //! A mergeable priority heap
 
-use std::collections::VecDeque;
+use std::borrow::Borrow;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+#[cfg(feature = "track_allocs")]
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::mpsc::{SendError, Sender};
 
+#[cfg(feature = "rand")]
+use rand::{Rng, RngExt};
 
-pub trait Item: PartialOrd + Copy {}
-impl<T: PartialOrd + Copy> Item for T {}
+#[cfg(feature = "async")]
+mod async_heap;
+#[cfg(feature = "async")]
+pub use async_heap::AsyncSkewHeap;
 
+mod builder;
+pub use builder::{BuildError, MergeStrategy, Overflow, SkewHeapBuilder};
+
+mod cached_key;
+pub use cached_key::CachedKey;
+
+mod concurrent;
+pub use concurrent::ConcurrentSkewHeap;
+
+mod double_buffered;
+pub use double_buffered::DoubleBufferedHeap;
+
+mod frozen;
+pub use frozen::FrozenHeap;
+
+#[cfg(feature = "huffman")]
+mod huffman;
+#[cfg(feature = "huffman")]
+pub use huffman::{build_huffman, HuffmanTree};
+
+mod minmax;
+pub use minmax::MinMaxSkewHeap;
+
+mod persistent;
+pub use persistent::PersistentSkewHeap;
+
+mod prioritized;
+pub use prioritized::Prioritized;
+
+mod tagged;
+pub use tagged::{tag_and_adopt, Tagged};
+
+mod top_k;
+pub use top_k::TopK;
+
+
+/// Builds a SkewHeap holding the given items, in the manner of vec![]. A min heap is built by
+/// default; prefix the items with `max;` to build a max heap instead.
+#[macro_export]
+macro_rules! skewheap {
+    (max; $($item:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut heap = $crate::SkewHeap::builder()
+            .order($crate::Order::Max)
+            .build()
+            .expect("unbounded heap is valid");
+        $( heap.put($item); )*
+        heap
+    }};
+    ($($item:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut heap = $crate::SkewHeap::new();
+        $( heap.put($item); )*
+        heap
+    }};
+}
+
+pub trait Item: PartialOrd {}
+impl<T: PartialOrd> Item for T {}
+
+
+/// The direction in which a heap is ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// The smallest item is at the top of the heap
+    Min,
+    /// The largest item is at the top of the heap
+    Max,
+}
+
+
+#[cfg(feature = "track_allocs")]
+static LIVE_NODES: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of nodes allocated by every heap in the process which have not yet been
+/// freed, counting the spare nodes heaps keep for reuse. Once every heap has been dropped, this
+/// is zero unless a node leaked.
+#[cfg(feature = "track_allocs")]
+pub fn live_node_count() -> usize {
+    LIVE_NODES.load(AtomicOrdering::SeqCst)
+}
+
+/// Records the allocation of a node, for live_node_count
+#[inline]
+pub(crate) fn node_allocated() {
+    #[cfg(feature = "track_allocs")]
+    LIVE_NODES.fetch_add(1, AtomicOrdering::SeqCst);
+}
+
+/// Records the freeing of a node, for live_node_count
+#[inline]
+pub(crate) fn node_freed() {
+    #[cfg(feature = "track_allocs")]
+    LIVE_NODES.fetch_sub(1, AtomicOrdering::SeqCst);
+}
 
 #[derive(Debug)]
 struct Node<T> {
     item:  T,
+    count: usize,
+    seq:   u64,
     left:  *mut Node<T>,
     right: *mut Node<T>,
 }
 
 impl<T: Item> Node<T> {
-    fn new(item: T) -> *mut Self {
+    fn new(item: T, seq: u64) -> *mut Self {
+        node_allocated();
         Box::into_raw(Box::new(Self {
-            item:  item,
+            item,
+            count: 1,
+            seq,
             left:  std::ptr::null_mut(),
             right: std::ptr::null_mut(),
         }))
     }
 
-    fn merge(a: *mut Self, b: *mut Self) -> *mut Self {
+    /// Merges two trees, using after(x, y) to decide whether node x must be ordered below node y
+    fn merge<F: Fn(&Self, &Self) -> bool>(a: *mut Self, b: *mut Self, after: &F) -> *mut Self {
         if a.is_null() {
             return b
         }
@@ -33,13 +146,12 @@ impl<T: Item> Node<T> {
         }
 
         unsafe{
-            // Swap args to preserve correct ordering if a > b
-            if (*a).item > (*b).item {
-                std::ptr::swap(a, b);
-            }
+            // Swap args to preserve correct ordering if a comes after b. Only the pointers are
+            // swapped, so every item stays in the node it was allocated with.
+            let (a, b) = if after(&*a, &*b) { (b, a) } else { (a, b) };
 
             // Build a new node from b and a's right child
-            let new_left_node = Node::merge(b, (*a).right);
+            let new_left_node = Node::merge(b, (*a).right, after);
 
             // Move a's left node to the right side
             (*a).right = (*a).left;
@@ -47,8 +159,88 @@ impl<T: Item> Node<T> {
             // Replace a's left node with the merger of b and a's right node
             (*a).left = new_left_node;
 
-            return a;
+            a
+        }
+    }
+
+    /// Merges two trees exactly as merge() does, building an identical tree, but in a loop rather
+    /// than by recursion. Where merge() would recurse, this instead records the slot awaiting the
+    /// result of the inner merge and continues with that merge's arguments.
+    fn merge_iterative<F>(mut a: *mut Self, mut b: *mut Self, after: &F) -> *mut Self
+    where
+        F: Fn(&Self, &Self) -> bool,
+    {
+        let mut root = std::ptr::null_mut();
+        let mut slot: *mut *mut Self = &mut root;
+
+        unsafe{
+            while !a.is_null() && !b.is_null() {
+                if after(&*a, &*b) {
+                    std::mem::swap(&mut a, &mut b);
+                }
+
+                *slot = a;
+
+                // a's left node is replaced by the merger of b and a's right node, computed next
+                let right = (*a).right;
+                (*a).right = (*a).left;
+                slot = &mut (*a).left;
+
+                a = b;
+                b = right;
+            }
+
+            *slot = if a.is_null() { b } else { a };
+        }
+
+        root
+    }
+}
+
+/// Walks every node of a tree depth first, yielding each alongside its depth (the root being at
+/// depth 0).
+struct Nodes<'a, T> {
+    stack: Vec<(&'a Node<T>, usize)>,
+}
+
+impl<'a, T> Nodes<'a, T> {
+    fn new(root: *mut Node<T>) -> Self {
+        let mut stack = vec![];
+
+        if !root.is_null() {
+            stack.push((unsafe{ &*root }, 0));
+        }
+
+        Self { stack }
+    }
+}
+
+impl<T: Item + Clone> Node<T> {
+    /// Returns an unlinked copy of this node
+    fn duplicate(&self) -> *mut Self {
+        let node = Node::new(self.item.clone(), self.seq);
+        unsafe{ (*node).count = self.count }
+        node
+    }
+}
+
+impl<'a, T> Iterator for Nodes<'a, T> {
+    type Item = (&'a Node<T>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, depth) = self.stack.pop()?;
+
+        unsafe{
+            if !node.right.is_null() {
+                self.stack.push((&*node.right, depth + 1));
+            }
+
+            if !node.left.is_null() {
+                self.stack.push((&*node.left, depth + 1));
+            }
         }
+
+        Some((node, depth))
     }
 }
 
@@ -57,39 +249,369 @@ impl<T: Item + std::fmt::Display> Node<T> {
         let indent_str = format!("{:width$}", "", width=(indent * 3));
 
         unsafe{
-            println!("{}Node: {}", indent_str, (*self).item);
+            println!("{}Node: {}", indent_str, self.item);
 
-            if !(*self).left.is_null() {
+            if !self.left.is_null() {
                 println!("{}   Left:", indent_str);
-                (*(*self).left).explain(indent + 2);
+                (*self.left).explain(indent + 2);
             }
 
-            if !(*self).right.is_null() {
+            if !self.right.is_null() {
                 println!("{}   Right:", indent_str);
-                (*(*self).right).explain(indent + 2);
+                (*self.right).explain(indent + 2);
             }
         }
     }
 }
 
 
-/// A skew heap is an unbounded priority (min) heap. It is paramaterized by the type of item to be
-/// stored in it. Items must implement PartialOrd and Clone.
+/// A skew heap is a priority heap. It is paramaterized by the type of item to be stored in it.
+/// Items must implement PartialOrd. By default, a SkewHeap is an unbounded min heap; use
+/// SkewHeapBuilder to configure its order or bound its capacity.
+///
+/// Items which compare as equal are not guaranteed to be taken in insertion order. However, the
+/// order is deterministic: the same sequence of operations on a heap always yields equal items in
+/// the same order.
 #[derive(Debug)]
 pub struct SkewHeap<T> {
-    count: usize,
-    root:  *mut Node<T>,
+    count:     usize,
+    root:      *mut Node<T>,
+    order:     Order,
+    capacity:  Option<usize>,
+    overflow:  Overflow,
+    none_last: bool,
+
+    // When sequenced, equal items are taken in the order they were put, according to the sequence
+    // number stamped on each node. Nodes are stamped from next_seq regardless.
+    sequenced: bool,
+    next_seq:  u64,
+
+    // When set, decides the order of items in place of order and none_last
+    comparator: Option<Comparator<T>>,
+
+    // How trees are merged; both strategies build identical trees
+    strategy:  MergeStrategy,
+
+    // When tracking the max, max points to the node holding the item which would be taken last,
+    // or is null if the heap is empty. Merging only ever relinks nodes, so the pointer stays valid
+    // until that node is released.
+    track_max: bool,
+    max:       *mut Node<T>,
+
+    // When interning, equal items share a single node whose count is incremented instead. Taking
+    // from such a node hands out clones of its item, made using this function.
+    interner:  Option<fn(&T) -> T>,
+
+    // When recycling, take() keeps freed nodes in the pool rather than returning them to the
+    // allocator
+    recycle:   bool,
+
+    // Nodes freed by reset_for_reuse() (or by take(), when recycling), kept for reuse by put().
+    // The items of these nodes have already been moved out or dropped, so they must be freed
+    // without dropping them.
+    pool:      Vec<*mut Node<T>>,
+}
+
+/// A comparator deciding the order of a heap's items at runtime; see SkewHeap::new_with_comparator
+pub type CompareFn<T> = dyn Fn(&T, &T) -> std::cmp::Ordering + Send + Sync;
+
+/// A comparator supplied at runtime, shared between a heap and its clones
+struct Comparator<T>(Arc<CompareFn<T>>);
+
+impl<T> Clone for Comparator<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> std::fmt::Debug for Comparator<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Comparator")
+    }
 }
 
+// A SkewHeap exclusively owns every node reachable from its root or pool, and only hands out
+// references to its items through &self or &mut self. It is therefore as safe to send or share
+// across threads as the items themselves.
+unsafe impl<T: Send> Send for SkewHeap<T> {}
+unsafe impl<T: Sync> Sync for SkewHeap<T> {}
+
 impl<T: Item> SkewHeap<T> {
     /// Returns a new SkewHeap
     pub fn new() -> Self {
         Self {
-            count: 0,
-            root:  std::ptr::null_mut(),
+            count:     0,
+            root:      std::ptr::null_mut(),
+            order:     Order::Min,
+            capacity:  None,
+            overflow:  Overflow::Panic,
+            none_last: false,
+            sequenced: false,
+            next_seq:  0,
+            comparator: None,
+            strategy:  MergeStrategy::Recursive,
+            track_max: false,
+            max:       std::ptr::null_mut(),
+            interner:  None,
+            recycle:   false,
+            pool:      Vec::new(),
+        }
+    }
+
+    /// Returns a new SkewHeap in which items that cannot be compared (such as a NaN float) are
+    /// treated as greater than every comparable item, so they sink to the bottom of the heap and
+    /// are taken last. This gives deterministic ordering for float data containing NaNs.
+    pub fn new_partial_none_last() -> Self {
+        let mut heap = Self::new();
+        heap.none_last = true;
+        heap
+    }
+
+    /// Returns a new SkewHeap whose items are ordered by a comparator chosen at runtime, placing
+    /// items for which cmp returns Less nearer the top. The comparator must be Send and Sync so
+    /// that the heap remains safe to share between threads.
+    pub fn new_with_comparator(cmp: Box<CompareFn<T>>) -> Self {
+        let mut heap = Self::new();
+        heap.comparator = Some(Comparator(Arc::from(cmp)));
+        heap
+    }
+
+    /// Returns a new SkewHeap which keeps track of the item it would take last, making peek_max()
+    /// O(1). This costs a comparison on every put, and a traversal of the tree whenever the
+    /// tracked item itself is taken.
+    pub fn with_max_tracking() -> Self {
+        let mut heap = Self::new();
+        heap.track_max = true;
+        heap
+    }
+
+    /// Builds a new SkewHeap from a vector of items by bottom-up pairwise merging. Each item starts
+    /// out as a single node tree in a queue; the two trees at the front of the queue are
+    /// repeatedly merged and the result sent to the back until a single tree remains. For large
+    /// inputs, this is faster than putting each item in turn.
+    pub fn heapify(items: Vec<T>) -> Self {
+        let mut heap = Self::new();
+        heap.count = items.len();
+
+        let nodes = items.into_iter().map(|item| heap.alloc_node(item)).collect();
+        heap.root = heap.merge_pairwise(nodes);
+        heap
+    }
+
+    /// Builds a new SkewHeap from a vector of items in O(n). This is another name for heapify(),
+    /// which already builds the tree by bottom-up pairwise merging; the "build skewheap" benchmark
+    /// compares it with putting each item in turn.
+    pub fn from_unsorted(items: Vec<T>) -> Self {
+        Self::heapify(items)
+    }
+
+    /// Rebuilds a min heap from the breadth first encoding produced by to_level_order, restoring
+    /// the exact tree structure. If the encoding does not satisfy the heap property, or holds
+    /// entries with no parent to attach to, the tree is instead rebuilt from its items. The size
+    /// of the result is the number of Some entries.
+    pub fn from_level_order(data: Vec<Option<T>>) -> Self {
+        let mut heap = Self::new();
+
+        let slots: Vec<Option<*mut Node<T>>> = data.into_iter()
+            .map(|item| item.map(|item| heap.alloc_node(item)))
+            .collect();
+
+        heap.count = slots.iter().flatten().count();
+
+        let mut slots = slots.into_iter();
+        let mut parents = VecDeque::new();
+        let mut valid = true;
+
+        if let Some(root) = slots.next().flatten() {
+            heap.root = root;
+            parents.push_back(root);
+        }
+
+        while let Some(parent) = parents.pop_front() {
+            for side in 0..2 {
+                let node = match slots.next() {
+                    Some(Some(node)) => node,
+                    _ => continue,
+                };
+
+                unsafe{
+                    valid &= !heap.after(&(*parent).item, &(*node).item);
+
+                    if side == 0 {
+                        (*parent).left = node;
+                    } else {
+                        (*parent).right = node;
+                    }
+                }
+
+                parents.push_back(node);
+            }
+        }
+
+        if !valid {
+            heap.rebuild();
+        }
+
+        // any entries left over had no parent in the encoding
+        let mut orphans: VecDeque<*mut Node<T>> = slots.flatten().collect();
+
+        if !orphans.is_empty() {
+            orphans.push_back(heap.root);
+            heap.root = heap.merge_pairwise(orphans);
+        }
+
+        heap
+    }
+
+    /// Merges all of the supplied heaps into one, keeping only the cap items which would be taken
+    /// first and discarding the rest. The result takes its configuration from the first heap. The
+    /// size of the result is the lesser of cap, the first heap's capacity (if bounded), and the
+    /// total number of items.
+    pub fn bounded_union(heaps: Vec<SkewHeap<T>>, cap: usize) -> SkewHeap<T> {
+        let mut heaps = heaps.into_iter();
+        let first = heaps.next().unwrap_or_default();
+
+        // the inputs may together exceed the first heap's capacity until trimmed
+        let capacity = first.capacity;
+        let mut union = first.empty_like();
+        union.capacity = None;
+        union.adopt(first);
+
+        for heap in heaps {
+            union.adopt(heap);
+        }
+
+        union.trim_to(capacity.map_or(cap, |capacity| capacity.min(cap)));
+        union.capacity = capacity;
+        union
+    }
+
+    /// Returns a builder to configure a new SkewHeap
+    pub fn builder() -> SkewHeapBuilder<T> {
+        SkewHeapBuilder::new()
+    }
+
+    /// Returns the order in which items are taken from the SkewHeap
+    #[inline]
+    pub fn order(&self) -> Order {
+        self.order
+    }
+
+    /// Returns the maximum number of items the SkewHeap may hold, or None if unbounded
+    #[inline]
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Returns true if the SkewHeap is bounded and cannot accept any more items
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.capacity.is_some_and(|capacity| self.count >= capacity)
+    }
+
+    /// Returns the number of items in the SkewHeap. Unlike a bare addition, this is guaranteed
+    /// never to have wrapped around: a heap that would overflow either panics or, if built with
+    /// Overflow::Saturate, stops counting at usize::MAX.
+    #[inline]
+    pub fn saturating_size(&self) -> usize {
+        self.count
+    }
+
+    /// Adds n to the item count according to the heap's overflow policy
+    #[inline]
+    fn grow(&mut self, n: usize) {
+        self.count = match self.overflow {
+            Overflow::Panic    => self.count.checked_add(n).expect("SkewHeap size overflowed usize"),
+            Overflow::Saturate => self.count.saturating_add(n),
+        };
+    }
+
+    /// True if a must be ordered below b in this heap
+    #[inline]
+    fn after(&self, a: &T, b: &T) -> bool {
+        if let Some(comparator) = &self.comparator {
+            return (comparator.0)(a, b) == std::cmp::Ordering::Greater
+        }
+
+        if self.none_last && a.partial_cmp(b).is_none() {
+            // an item that cannot even be compared with itself is the incomparable one
+            let incomparable = |x: &T| x.partial_cmp(x).is_none();
+            return incomparable(a) && !incomparable(b)
+        }
+
+        match self.order {
+            Order::Min => a > b,
+            Order::Max => a < b,
+        }
+    }
+
+    /// True if node a must be ordered below node b in this heap, breaking ties between equal items
+    /// by sequence number if the heap is sequenced
+    #[inline]
+    fn after_node(&self, a: &Node<T>, b: &Node<T>) -> bool {
+        if self.after(&a.item, &b.item) {
+            return true
+        }
+
+        self.sequenced && a.seq > b.seq && !self.after(&b.item, &a.item)
+    }
+
+    /// Compares two items by the order in which this heap would take them
+    fn heap_cmp(&self, a: &T, b: &T) -> std::cmp::Ordering {
+        if self.after(a, b) {
+            std::cmp::Ordering::Greater
+        } else if self.after(b, a) {
+            std::cmp::Ordering::Less
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    }
+
+    /// Merges two trees according to this heap's order
+    fn merge(&self, a: *mut Node<T>, b: *mut Node<T>) -> *mut Node<T> {
+        self.merge_by(a, b, &|x, y| self.after_node(x, y))
+    }
+
+    /// Merges two trees using after(x, y) to order nodes, by this heap's merge strategy
+    fn merge_by<F>(&self, a: *mut Node<T>, b: *mut Node<T>, after: &F) -> *mut Node<T>
+    where
+        F: Fn(&Node<T>, &Node<T>) -> bool,
+    {
+        match self.strategy {
+            MergeStrategy::Recursive => Node::merge(a, b, after),
+            MergeStrategy::Iterative => Node::merge_iterative(a, b, after),
         }
     }
 
+    /// Merges a queue of trees into one by repeatedly merging the two trees at the front and
+    /// sending the result to the back.
+    fn merge_pairwise(&self, mut queue: VecDeque<*mut Node<T>>) -> *mut Node<T> {
+        while queue.len() > 1 {
+            let a = queue.pop_front().unwrap();
+            let b = queue.pop_front().unwrap();
+            queue.push_back(self.merge(a, b));
+        }
+
+        queue.pop_front().unwrap_or(std::ptr::null_mut())
+    }
+
+    /// Returns a new, empty heap with the same configuration as this one
+    fn empty_like(&self) -> Self {
+        let mut heap = Self::new();
+        heap.order = self.order;
+        heap.capacity = self.capacity;
+        heap.overflow = self.overflow;
+        heap.none_last = self.none_last;
+        heap.sequenced = self.sequenced;
+        heap.next_seq = self.next_seq;
+        heap.comparator = self.comparator.clone();
+        heap.strategy = self.strategy;
+        heap.track_max = self.track_max;
+        heap.interner = self.interner;
+        heap.recycle = self.recycle;
+        heap
+    }
+
     /// Returns the number of items in the SkewHeap
     #[inline]
     pub fn size(&self) -> usize {
@@ -102,183 +624,4183 @@ impl<T: Item> SkewHeap<T> {
         self.root.is_null()
     }
 
-    /// Inserts an item into the heap and returns the new size
+    /// Inserts an item into the heap and returns the new size. Panics if the heap is full.
     pub fn put(&mut self, item: T) -> usize {
-        let node = Node::new(item);
+        assert!(!self.is_full(), "SkewHeap is at capacity");
+        self.grow(1);
+
+        if self.interner.is_some() {
+            if let Some(node) = self.find_on_insertion_path(&item) {
+                unsafe{ (*node).count += 1 }
+                return self.count
+            }
+        }
+
+        let node = self.alloc_node(item);
 
         if self.is_empty() {
             self.root = node;
         } else {
-            self.root = Node::merge(self.root, node);
+            self.root = self.merge(self.root, node);
         }
 
-        self.count += 1;
         self.count
     }
 
-    /// Removes and retrieves the top item from the heap
-    pub fn take(&mut self) -> Option<T> {
-        if self.is_empty() {
-            return None
+    /// Inserts an item into the heap, unless it is equal to the top item, in which case it is
+    /// passed to combine along with a mutable reference to the top item, to be merged into it
+    /// without adding a node. If combine reorders the top item, it is moved to its new position.
+    /// Returns the new size.
+    pub fn put_coalescing<F: FnOnce(&mut T, T)>(&mut self, item: T, combine: F) -> usize
+    where
+        T: PartialEq,
+    {
+        if self.root.is_null() || unsafe{ (*self.root).item != item } {
+            return self.put(item)
         }
 
         let root = self.root;
-        let item;
 
         unsafe{
-            item = (*root).item;
-            self.root = Node::merge((*root).left, (*root).right);
+            combine(&mut (*root).item, item);
 
-            // free old root node by giving ownership of it to Box
-            Box::from_raw(root);
-        }
+            let (left, right) = ((*root).left, (*root).right);
+            let displaced = (!left.is_null() && self.after_node(&*root, &*left))
+                || (!right.is_null() && self.after_node(&*root, &*right));
 
-        self.count -= 1;
-        Some(item)
-    }
+            if displaced {
+                (*root).left = std::ptr::null_mut();
+                (*root).right = std::ptr::null_mut();
+                self.root = self.merge(self.merge(left, right), root);
+            }
+        }
 
-    /// Retrieves the top item from the heap without removing it
-    pub fn peek(&mut self) -> Option<T> {
-        if self.is_empty() {
-            return None
+        if self.max == root {
+            self.refresh_max();
+        } else {
+            self.note_max(root);
         }
 
-        unsafe{ Some((*self.root).item) }
+        self.count
     }
 
-    /// Merge another skew heap into this one. Once merged, the other heap is destroyed.
-    pub fn adopt(&mut self, mut other: SkewHeap<T>) {
-        self.root = Node::merge(self.root, other.root);
-        self.count += other.count;
-
-        // self has taken possession of other's node pointers. We must remove the root pointer from
-        // other and set its count to 0 in order to prevent drop() from attempting to free other's
-        // root tree.
-        other.root = std::ptr::null_mut();
-        other.count = 0;
-    }
-}
+    /// Inserts every item from iter, first merging them into a balanced tree of their own by
+    /// bottom-up pairwise merging (as in heapify) and then merging that tree into this heap in a
+    /// single step. For large batches, this is faster than putting each item in turn. Panics if
+    /// the heap would exceed its capacity, in which case none of the items are inserted.
+    pub fn merge_iter(&mut self, iter: impl IntoIterator<Item = T>) {
+        let items: Vec<T> = iter.into_iter().collect();
 
-impl<T: Item + std::fmt::Display> SkewHeap<T> {
-    /// Prints out the entire tree structure for debugging
-    pub fn explain(&self) {
-        println!("SkewHeap<size={}>", self.count);
+        assert!(
+            self.capacity.is_none_or(|capacity| items.len() <= capacity.saturating_sub(self.count)),
+            "SkewHeap is at capacity"
+        );
 
-        if !self.root.is_null() {
-            unsafe{ (*self.root).explain(1) }
+        // interned items must each be looked up, so they are put one at a time
+        if self.interner.is_some() {
+            self.extend(items);
+            return
         }
+
+        self.grow(items.len());
+
+        let nodes = items.into_iter().map(|item| self.alloc_node(item)).collect();
+        let batch = self.merge_pairwise(nodes);
+        self.root = self.merge(self.root, batch);
     }
-}
 
-impl<T> Drop for SkewHeap<T> {
-    fn drop(&mut self) {
-        if !self.root.is_null() {
-            let mut stack = VecDeque::from([self.root]);
+    /// Inserts an item which the caller knows will be taken after every item already in the
+    /// heap (e.g. a monotonically increasing timestamp in a min heap) and returns the new size.
+    /// The resulting tree is identical to that built by put(), but no comparisons are made on
+    /// the way down. If the hint is wrong, the heap's ordering is silently corrupted, although
+    /// this remains memory safe; debug builds check the hint against the new item's parent.
+    pub fn put_max_hint(&mut self, item: T) -> usize {
+        if self.is_empty() || self.interner.is_some() {
+            return self.put(item)
+        }
 
-            loop {
-                if let Some(node) = stack.pop_front() {
-                    unsafe{
-                        if !(*node).left.is_null() {
-                            stack.push_front((*node).left);
-                        }
+        assert!(!self.is_full(), "SkewHeap is at capacity");
+        self.grow(1);
 
-                        if !(*node).right.is_null() {
-                            stack.push_front((*node).right);
-                        }
+        let node = self.alloc_node(item);
+        let mut parent = self.root;
 
-                        Box::from_raw(node);
-                    }
-                }
-                else {
+        // Merging in an item ordered after everything else swaps the children of every node along
+        // the right spine, leaving the item as the left child of the last one
+        unsafe{
+            loop {
+                let right = (*parent).right;
+                (*parent).right = (*parent).left;
+
+                if right.is_null() {
+                    debug_assert!(!self.after_node(&*parent, &*node), "put_max_hint item is out of order");
+                    (*parent).left = node;
                     break
                 }
+
+                (*parent).left = right;
+                parent = right;
             }
         }
+
+        self.count
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::SkewHeap;
+    /// Searches the nodes which a new item would be merged past on its way into the tree (the
+    /// prefix of the right spine not ordered after it) for one holding an equal item.
+    fn find_on_insertion_path(&self, item: &T) -> Option<*mut Node<T>> {
+        let mut node = self.root;
 
-    use rand::thread_rng;
-    use rand::seq::SliceRandom;
+        unsafe{
+            while !node.is_null() && !self.after(&(*node).item, item) {
+                if (*node).item == *item {
+                    return Some(node)
+                }
 
-    #[test]
-    fn test_positive_path() {
-        let mut skew = SkewHeap::new();
+                node = (*node).right;
+            }
+        }
 
-        assert!(skew.is_empty(), "initially empty");
-        assert_eq!(skew.peek(), None, "peek returns None when is_empty");
-        assert_eq!(skew.take(), None, "take returns None when is_empty");
+        None
+    }
 
-        assert_eq!(skew.put(10), 1, "put returns new size");
-        assert_eq!(skew.peek(), Some(10), "peek returns top entry after put");
-        assert_eq!(skew.size(), 1, "size returns expected count after put");
-        assert!(!skew.is_empty(), "is_empty false after put");
+    /// Inserts an item into the heap and returns the new size, or hands the item back if the heap
+    /// is full.
+    pub fn try_put(&mut self, item: T) -> Result<usize, T> {
+        if self.is_full() {
+            return Err(item)
+        }
 
-        assert_eq!(skew.put(3), 2, "put returns new size");
-        assert_eq!(skew.peek(), Some(3), "peek returns top entry after put");
-        assert_eq!(skew.size(), 2, "size returns expected count after put");
-        assert!(!skew.is_empty(), "is_empty false after put");
+        Ok(self.put(item))
+    }
 
-        assert_eq!(skew.put(15), 3, "put returns new size");
-        assert_eq!(skew.peek(), Some(3), "peak returns top entry after put");
-        assert_eq!(skew.size(), 3, "size returns expected count after put");
-        assert!(!skew.is_empty(), "is_empty false after put");
+    /// Removes and retrieves the top item from the heap. The freed node is returned to the
+    /// allocator, unless the heap was built to recycle nodes.
+    pub fn take(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None
+        }
 
-        assert_eq!(skew.take(), Some(3), "take returns top entry");
-        assert_eq!(skew.peek(), Some(10), "peek returns top entry after take");
-        assert_eq!(skew.size(), 2, "size returns expected count after take");
-        assert!(!skew.is_empty(), "is_empty false when > 0 entries");
+        let root = self.root;
+        self.count -= 1;
 
-        assert_eq!(skew.take(), Some(10), "take returns top entry");
-        assert_eq!(skew.peek(), Some(15), "peek returns top entry after take");
-        assert_eq!(skew.size(), 1, "size returns expected count after take");
-        assert!(!skew.is_empty(), "is_empty false when > 0 entries");
+        unsafe{
+            // an interned node holding more than one item stays put, handing out a clone
+            if (*root).count > 1 {
+                debug_assert!(self.interner.is_some(), "shared node in a heap that does not intern");
+                (*root).count -= 1;
+                return self.interner.map(|clone| clone(&(*root).item))
+            }
 
-        assert_eq!(skew.take(), Some(15), "take returns top entry");
-        assert_eq!(skew.peek(), None, "peek returns None after final entry returned by take");
-        assert_eq!(skew.size(), 0, "size is 0 after final entry returned by take");
-        assert!(skew.is_empty(), "is_empty true after final entry returned by take");
+            self.root = self.merge((*root).left, (*root).right);
+        }
+
+        Some(self.release_node(root))
     }
 
-    #[test]
-    fn test_merge_heaps() {
+    /// Removes and retrieves the first item that would be taken for which f returns true, leaving
+    /// every other item in the heap. The nodes of non-matching items ahead of it are detached in
+    /// turn until a match reaches the top, then merged back in, so skipped items keep their nodes
+    /// (and sequence numbers).
+    pub fn take_matching<F: Fn(&T) -> bool>(&mut self, f: F) -> Option<T> {
+        let mut skipped = VecDeque::new();
+        let mut found = None;
+
+        while !self.root.is_null() {
+            let root = self.root;
+
+            if unsafe{ f(&(*root).item) } {
+                found = self.take();
+                break
+            }
+
+            unsafe{
+                self.root = self.merge((*root).left, (*root).right);
+                (*root).left = std::ptr::null_mut();
+                (*root).right = std::ptr::null_mut();
+            }
+
+            skipped.push_back(root);
+        }
+
+        // taking the match may have recomputed the max without the detached nodes
+        for &node in &skipped {
+            self.note_max(node);
+        }
+
+        let skipped = self.merge_pairwise(skipped);
+        self.root = self.merge(self.root, skipped);
+
+        found
+    }
+
+    /// Removes every item from the heap in order, pairing each with the sequence number stamped on
+    /// it when it was put, which counts up from zero with each insertion. Every heap stamps its
+    /// items, but only a sequenced heap uses the stamps to take equal items first in, first out.
+    /// The copies of an interned item share a single stamp.
+    pub fn drain_with_seq(&mut self) -> Vec<(T, u64)> {
+        let mut items = Vec::with_capacity(self.count);
+
+        while !self.root.is_null() {
+            let seq = unsafe{ (*self.root).seq };
+
+            if let Some(item) = self.take() {
+                items.push((item, seq));
+            }
+        }
+
+        items
+    }
+
+    /// Removes every item from the heap in order, passing each to f as it is taken. Unlike
+    /// collecting the items into a vector, this never holds more than one drained item at a time.
+    pub fn drain_each<F: FnMut(T)>(&mut self, mut f: F) {
+        while let Some(item) = self.take() {
+            f(item);
+        }
+    }
+
+    /// Returns an iterator which drains the heap in order, coalescing each run of equal items
+    /// into a single (item, run length) pair. The run lengths sum to the heap's original size.
+    pub fn drain_grouped(&mut self) -> impl Iterator<Item = (T, usize)> + '_ {
+        std::iter::from_fn(move || {
+            let item = self.take()?;
+            let mut run = 1;
+
+            while self.peek() == Some(&item) {
+                self.take();
+                run += 1;
+            }
+
+            Some((item, run))
+        })
+    }
+
+    /// Removes every item from the heap in order, sending each on tx, and returns the number of
+    /// items sent. If the receiver has hung up, the item which could not be sent is handed back
+    /// in the error, and every item after it is left in the heap.
+    pub fn drain_to_sender(&mut self, tx: &Sender<T>) -> Result<usize, SendError<T>> {
+        let mut sent = 0;
+
+        while let Some(item) = self.take() {
+            tx.send(item)?;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    /// Removes and returns, in order, every item up to and including now, for a min heap of
+    /// deadlines used as a timer queue. Later deadlines are left in the heap.
+    pub fn pop_expired(&mut self, now: T) -> Vec<T> {
+        let mut expired = vec![];
+
+        while self.peek().is_some_and(|deadline| *deadline <= now) {
+            expired.extend(self.take());
+        }
+
+        expired
+    }
+
+    /// Takes items in order for as long as their combined cost stays within budget, stopping at
+    /// the first item which would exceed it, e.g. to consume a bounded amount of work per tick.
+    /// That item, and all those after it, are left in the heap.
+    pub fn drain_budget<F: Fn(&T) -> u64>(&mut self, budget: u64, cost: F) -> Vec<T> {
+        let mut items = vec![];
+        let mut spent: u64 = 0;
+
+        while let Some(item) = self.peek() {
+            match spent.checked_add(cost(item)) {
+                Some(total) if total <= budget => spent = total,
+                _ => break,
+            }
+
+            items.extend(self.take());
+        }
+
+        items
+    }
+
+    /// Takes the top item and immediately puts it back after any items equal to it, returning a
+    /// reference to the cycled item. Repeated calls rotate through a set of equal items, which is
+    /// useful for round-robin scheduling among tasks of the same priority. The size of the heap
+    /// is unchanged.
+    ///
+    /// A sequenced heap (see SkewHeapBuilder::sequenced) guarantees a full rotation, since the
+    /// cycled item is stamped with a new sequence number. Otherwise, the item is only ordered
+    /// after the equal items it meets on its way back into the tree, so some equal items may be
+    /// cycled more often than others.
+    pub fn cycle_min(&mut self) -> Option<&T> {
+        let item = self.take()?;
+
+        self.grow(1);
+
+        // as in put(), an interned item rejoins an equal node rather than taking one of its own
+        if self.interner.is_some() {
+            if let Some(node) = self.find_on_insertion_path(&item) {
+                unsafe{
+                    (*node).count += 1;
+                    return Some(&(*node).item)
+                }
+            }
+        }
+
+        let node = self.alloc_node(item);
+
+        if self.sequenced || self.interner.is_some() {
+            self.root = self.merge(self.root, node);
+        } else {
+            // the cycled node is ordered after every node it is not strictly before
+            self.root = self.merge_by(self.root, node, &|a, b| {
+                if std::ptr::eq(a, node) {
+                    !self.after(&b.item, &a.item)
+                } else {
+                    self.after_node(a, b)
+                }
+            });
+        }
+
+        unsafe{ Some(&(*node).item) }
+    }
+
+    /// Empties the heap, dropping every item but keeping the freed nodes for reuse by later calls
+    /// to put(). This is intended for hot loops (such as benchmarks) which repeatedly fill and
+    /// empty a single heap, sparing them an allocation per item on each pass. To also keep the
+    /// nodes freed by take(), build the heap with SkewHeapBuilder::recycling.
+    pub fn reset_for_reuse(&mut self) {
+        let mut stack = vec![];
+
+        if !self.root.is_null() {
+            stack.push(self.root);
+        }
+
+        self.root = std::ptr::null_mut();
+        self.max = std::ptr::null_mut();
+        self.count = 0;
+        self.pool.reserve(stack.len());
+
+        while let Some(node) = stack.pop() {
+            unsafe{
+                if !(*node).left.is_null() {
+                    stack.push((*node).left);
+                }
+
+                if !(*node).right.is_null() {
+                    stack.push((*node).right);
+                }
+
+                std::ptr::drop_in_place(&mut (*node).item);
+            }
+
+            self.pool.push(node);
+        }
+    }
+
+    /// Frees spare nodes kept for reuse beyond the first max_free, returning their memory to the
+    /// allocator. After a spike in size, this stops the heap from holding on to memory it may
+    /// never need again, at the cost of allocating afresh should it grow that large once more.
+    pub fn shrink_pool_to(&mut self, max_free: usize) {
+        while self.pool.len() > max_free {
+            if let Some(node) = self.pool.pop() {
+                // pooled nodes hold no item, so they are freed without dropping one
+                node_freed();
+                unsafe{ drop(Box::from_raw(node as *mut MaybeUninit<Node<T>>)) }
+            }
+        }
+
+        self.pool.shrink_to(max_free);
+    }
+
+    /// Applies f to every item in the heap (in no particular order), then rebuilds the tree once to
+    /// restore the heap's ordering, which f may have disturbed. This is much cheaper than taking,
+    /// modifying, and putting back each item in turn.
+    pub fn for_each_mut<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        let mut stack = vec![];
+
+        if !self.root.is_null() {
+            stack.push(self.root);
+        }
+
+        while let Some(node) = stack.pop() {
+            unsafe{
+                if !(*node).left.is_null() {
+                    stack.push((*node).left);
+                }
+
+                if !(*node).right.is_null() {
+                    stack.push((*node).right);
+                }
+
+                f(&mut (*node).item);
+            }
+        }
+
+        self.rebuild();
+        self.refresh_max();
+    }
+
+    /// Returns the number of nodes along the longest path from the root to a leaf, or 0 for an
+    /// empty heap
+    pub fn height(&self) -> usize {
+        Nodes::new(self.root).map(|(_, depth)| depth + 1).max().unwrap_or(0)
+    }
+
+    /// Measures the shape of the tree in a single traversal
+    pub fn shape_metrics(&self) -> ShapeMetrics {
+        let mut metrics = ShapeMetrics {
+            size:                 self.count,
+            height:               0,
+            right_spine_len:      0,
+            internal_path_length: 0,
+        };
+
+        let mut stack = vec![];
+
+        if !self.root.is_null() {
+            stack.push((self.root, 0, true));
+        }
+
+        while let Some((node, depth, on_spine)) = stack.pop() {
+            metrics.height = metrics.height.max(depth + 1);
+            metrics.internal_path_length += depth;
+
+            if on_spine {
+                metrics.right_spine_len += 1;
+            }
+
+            unsafe{
+                if !(*node).left.is_null() {
+                    stack.push(((*node).left, depth + 1, false));
+                }
+
+                if !(*node).right.is_null() {
+                    stack.push(((*node).right, depth + 1, on_spine));
+                }
+            }
+        }
+
+        metrics
+    }
+
+    /// Returns the number of nodes along the path from the root following right children. Taking
+    /// the top item costs time proportional to the right spines of the root's two subtrees.
+    pub fn right_spine_len(&self) -> usize {
+        let mut len = 0;
+        let mut node = self.root;
+
+        while !node.is_null() {
+            len += 1;
+            node = unsafe{ (*node).right };
+        }
+
+        len
+    }
+
+    /// Rebuilds the heap by bottom-up pairwise merging if its right spine is longer than
+    /// max_spine, guarding against pathological shapes produced by adversarial input. A rebuilt
+    /// heap's right spine is roughly logarithmic in its size, so a max_spine below that cannot
+    /// be met.
+    pub fn rebalance(&mut self, max_spine: usize) {
+        if self.right_spine_len() > max_spine {
+            self.rebuild();
+        }
+    }
+
+    /// Detaches every node from the tree and merges them back together pairwise, restoring the
+    /// heap's ordering regardless of the tree's prior state
+    fn rebuild(&mut self) {
+        let mut queue = VecDeque::with_capacity(self.count);
+        let mut stack = vec![];
+
+        if !self.root.is_null() {
+            stack.push(self.root);
+        }
+
+        while let Some(node) = stack.pop() {
+            unsafe{
+                if !(*node).left.is_null() {
+                    stack.push((*node).left);
+                }
+
+                if !(*node).right.is_null() {
+                    stack.push((*node).right);
+                }
+
+                (*node).left = std::ptr::null_mut();
+                (*node).right = std::ptr::null_mut();
+            }
+
+            queue.push_back(node);
+        }
+
+        self.root = self.merge_pairwise(queue);
+    }
+
+    /// Reduces the heap to the k items which would be taken first (the k smallest, in a min
+    /// heap), dropping the rest. This is useful for periodically capping an unbounded queue.
+    pub fn trim_to(&mut self, k: usize) {
+        if self.count <= k {
+            return
+        }
+
+        let kept: Vec<T> = std::iter::from_fn(|| self.take()).take(k).collect();
+
+        if self.recycle {
+            self.reset_for_reuse();
+        } else {
+            let root = std::mem::replace(&mut self.root, std::ptr::null_mut());
+            self.max = std::ptr::null_mut();
+            self.count = 0;
+            drop(FreeNodes(vec![root]));
+        }
+
+        for item in kept {
+            self.put(item);
+        }
+    }
+
+    /// Returns a new node holding item, reusing a node from the pool if one is available
+    fn alloc_node(&mut self, item: T) -> *mut Node<T> {
+        let node = match self.pool.pop() {
+            Some(node) => {
+                unsafe{
+                    std::ptr::write(node, Node {
+                        item,
+                        count: 1,
+                        seq:   self.next_seq,
+                        left:  std::ptr::null_mut(),
+                        right: std::ptr::null_mut(),
+                    });
+                }
+
+                node
+            },
+            None => Node::new(item, self.next_seq),
+        };
+
+        self.next_seq = self.next_seq.wrapping_add(1);
+
+        // every caller links the new node into the tree
+        self.note_max(node);
+        node
+    }
+
+    /// Moves the item out of a node which has already been unlinked from the tree, then frees the
+    /// node, or returns it to the pool when recycling.
+    fn release_node(&mut self, node: *mut Node<T>) -> T {
+        let item = unsafe{ std::ptr::read(&(*node).item) };
+
+        if self.recycle {
+            self.pool.push(node);
+        } else {
+            // the item has been moved out, so the node is freed without dropping it
+            node_freed();
+            unsafe{ drop(Box::from_raw(node as *mut MaybeUninit<Node<T>>)) }
+        }
+
+        if node == self.max {
+            self.refresh_max();
+        }
+
+        item
+    }
+
+    /// Retrieves a reference to the top item from the heap without removing it.
+    ///
+    /// Breaking change: peek formerly took &mut self and returned a copy of the top item. That
+    /// behavior is now provided by peek_copied.
+    pub fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None
+        }
+
+        unsafe{ Some(&(*self.root).item) }
+    }
+
+    /// Retrieves a reference to the item which would be taken last (the largest, in a min heap)
+    /// without removing it. This is O(1) for a heap built with with_max_tracking(); otherwise,
+    /// every leaf of the tree must be examined.
+    pub fn peek_max(&self) -> Option<&T> {
+        let max = if self.track_max { self.max } else { self.find_max() };
+        unsafe{ max.as_ref().map(|node| &node.item) }
+    }
+
+    /// Finds the node holding the item which would be taken last. Every node is ordered before
+    /// its children, so that item must be held by a leaf.
+    fn find_max(&self) -> *mut Node<T> {
+        Nodes::new(self.root)
+            .filter(|(node, _)| node.left.is_null() && node.right.is_null())
+            .map(|(node, _)| node)
+            .reduce(|max, node| if self.after_node(node, max) { node } else { max })
+            .map_or(std::ptr::null_mut(), |node| node as *const Node<T> as *mut Node<T>)
+    }
+
+    /// Updates the tracked max with a node (or the max node of a subtree) newly linked into the
+    /// tree
+    fn note_max(&mut self, node: *mut Node<T>) {
+        if self.track_max && !node.is_null()
+            && (self.max.is_null() || unsafe{ self.after_node(&*node, &*self.max) })
+        {
+            self.max = node;
+        }
+    }
+
+    /// Updates the tracked max with the max node of a heap whose tree is being adopted. An
+    /// untracked heap's max must be searched for, so this is skipped unless this heap tracks it.
+    fn note_max_of(&mut self, other: &SkewHeap<T>) {
+        if self.track_max {
+            self.note_max(if other.track_max { other.max } else { other.find_max() });
+        }
+    }
+
+    /// Recomputes the tracked max after the tree has changed in bulk
+    fn refresh_max(&mut self) {
+        if self.track_max {
+            self.max = self.find_max();
+        }
+    }
+
+    /// Merge another skew heap into this one. Once merged, the other heap is destroyed. Both heaps
+    /// must share the same order. Panics if the merged heap would exceed this heap's capacity. A
+    /// heap ordered differently from this one (by comparator, placement of None, or sequencing),
+    /// or an interning heap adopted by one that does not intern, must first be rebuilt, which is
+    /// O(n), or O(n log n) if this heap is sequenced. Items adopted by a sequenced heap are taken
+    /// after its equal items.
+    pub fn adopt(&mut self, other: SkewHeap<T>) {
+        debug_assert_eq!(self.order, other.order, "adopted heap has a different order");
+        let mut other = self.compatible(other);
+        assert!(
+            self.capacity.is_none_or(|capacity| other.count <= capacity.saturating_sub(self.count)),
+            "SkewHeap is at capacity"
+        );
+
+        // grow first so that an overflow panic leaves both trees intact
+        self.grow(other.count);
+        self.root = self.merge(self.root, other.root);
+        self.note_max_of(&other);
+
+        if self.sequenced {
+            self.next_seq = other.next_seq;
+        }
+
+        // self has taken possession of other's node pointers. We must remove the root pointer from
+        // other and set its count to 0 in order to prevent drop() from attempting to free other's
+        // root tree.
+        other.root = std::ptr::null_mut();
+        other.count = 0;
+
+        // other's spare nodes may as well be reused here, too
+        self.pool.append(&mut other.pool);
+    }
+
+    /// Merges every one of others into this heap, returning the new size. The others are first
+    /// merged together pairwise, which keeps the merged tree shallower than adopting each in
+    /// turn, and the result is then merged into this heap. Once merged, the others are destroyed.
+    /// All heaps must share the same order. Panics if the merged heap would exceed this heap's
+    /// capacity.
+    pub fn adopt_all(&mut self, others: Vec<SkewHeap<T>>) -> usize {
+        let total = others.iter().fold(0usize, |total, other| total.saturating_add(other.count));
+        assert!(
+            self.capacity.is_none_or(|capacity| total <= capacity.saturating_sub(self.count)),
+            "SkewHeap is at capacity"
+        );
+
+        // grow first so that an overflow panic leaves every tree intact
+        self.grow(total);
+
+        let mut roots = VecDeque::with_capacity(others.len());
+
+        for other in others {
+            debug_assert_eq!(self.order, other.order, "adopted heap has a different order");
+            let mut other = self.compatible(other);
+
+            if self.sequenced {
+                self.next_seq = other.next_seq;
+            }
+
+            if !other.root.is_null() {
+                roots.push_back(other.root);
+                self.note_max_of(&other);
+            }
+
+            // as in adopt(), other no longer owns its tree
+            other.root = std::ptr::null_mut();
+            other.count = 0;
+            self.pool.append(&mut other.pool);
+        }
+
+        let merged = self.merge_pairwise(roots);
+        self.root = self.merge(self.root, merged);
+        self.count
+    }
+
+    /// Consumes this heap and other, returning a new heap holding the items of both. This is the
+    /// functional form of adopt(), and the result takes this heap's configuration.
+    pub fn merged(mut self, other: Self) -> Self {
+        self.adopt(other);
+        self
+    }
+
+    /// Exchanges the contents of this heap with other's in O(1), without moving any items. Each
+    /// heap's configuration (order, capacity, and so on) travels with its items.
+    pub fn swap(&mut self, other: &mut SkewHeap<T>) {
+        std::mem::swap(self, other);
+    }
+
+    /// Merges another skew heap into this one like adopt(), returning the new size, but first
+    /// checks that the merge is sound, returning an error rather than panicking or corrupting
+    /// this heap if not. The other heap's size is always checked for consistency with whether
+    /// its tree is empty, and debug builds also recount its items. Once merged, or rejected, the
+    /// other heap is destroyed.
+    pub fn try_adopt(&mut self, other: SkewHeap<T>) -> Result<usize, AdoptError> {
+        if self.order != other.order {
+            return Err(AdoptError::OrderMismatch)
+        }
+
+        if other.root.is_null() != (other.count == 0) {
+            return Err(AdoptError::Inconsistent { count: other.count, held: other.held() })
+        }
+
+        if cfg!(debug_assertions) && other.held() != other.count {
+            return Err(AdoptError::Inconsistent { count: other.count, held: other.held() })
+        }
+
+        if self.capacity.is_some_and(|capacity| other.count > capacity.saturating_sub(self.count)) {
+            return Err(AdoptError::AtCapacity)
+        }
+
+        self.adopt(other);
+        Ok(self.count)
+    }
+
+    /// Prepares another heap's tree for merging into this one. A tree ordered by a different
+    /// comparator or placement of None would break this heap's ordering, and a node of an
+    /// interning heap may hold several items, which only a heap that interns can hand out. In
+    /// either case, the other heap's items are first rebuilt into a tree of this heap's
+    /// configuration. If this heap is sequenced, the other heap's items are stamped to follow
+    /// this heap's, in the order the other heap would have taken them, and the returned heap's
+    /// next_seq follows its last stamp.
+    fn compatible(&self, mut other: SkewHeap<T>) -> SkewHeap<T> {
+        let same_comparator = match (&self.comparator, &other.comparator) {
+            (None, None)       => true,
+            (Some(a), Some(b)) => Arc::ptr_eq(&a.0, &b.0),
+            _                  => false,
+        };
+
+        if same_comparator
+            && self.none_last == other.none_last
+            && self.sequenced == other.sequenced
+            && (self.interner.is_some() || other.interner.is_none())
+        {
+            if self.sequenced {
+                other.restamp_from(self.next_seq);
+            }
+
+            return other
+        }
+
+        // capacity is checked by the caller against the other heap's size
+        let mut rebuilt = self.empty_like();
+        rebuilt.capacity = None;
+
+        if self.sequenced {
+            rebuilt.merge_iter(std::iter::from_fn(|| other.take()));
+        } else {
+            rebuilt.merge_iter(other.dismantle());
+        }
+
+        rebuilt
+    }
+
+    /// Shifts every node's sequence number so that the earliest begins at first, keeping their
+    /// relative order, which only affects equal items and so leaves the tree valid.
+    fn restamp_from(&mut self, first: u64) {
+        let earliest = match Nodes::new(self.root).map(|(node, _)| node.seq).min() {
+            Some(earliest) => earliest,
+            None => {
+                self.next_seq = first;
+                return
+            }
+        };
+
+        let offset = first.wrapping_sub(earliest);
+        let mut stack = vec![self.root];
+
+        while let Some(node) = stack.pop() {
+            if node.is_null() {
+                continue
+            }
+
+            unsafe {
+                (*node).seq = (*node).seq.wrapping_add(offset);
+                stack.push((*node).left);
+                stack.push((*node).right);
+            }
+        }
+
+        self.next_seq = self.next_seq.wrapping_add(offset);
+    }
+
+    /// Returns the number of items actually held in the tree, counted by traversal
+    fn held(&self) -> usize {
+        Nodes::new(self.root).map(|(node, _)| node.count).sum()
+    }
+
+    /// Merge another skew heap into this one, discarding any of other's items that are already
+    /// present in this heap. Once merged, the other heap is destroyed.
+    pub fn adopt_dedup(&mut self, other: SkewHeap<T>) {
+        let unique: Vec<T> = other.dismantle()
+            .into_iter()
+            .filter(|item| !self.contains(item))
+            .collect();
+
+        for item in unique {
+            self.put(item);
+        }
+    }
+
+    /// Removes every item present in to_remove, returning the number of items removed. For
+    /// example, this may be used to cancel many queued tasks at once. The heap is drained and
+    /// rebuilt from the remaining items, so this is O(n log n) regardless of how many match.
+    pub fn remove_all(&mut self, to_remove: &HashSet<T>) -> usize
+    where
+        T: Eq + Hash,
+    {
+        let before = self.count;
+        let kept: Vec<T> = std::iter::from_fn(|| self.take())
+            .filter(|item| !to_remove.contains(item))
+            .collect();
+
+        // items are put back in the order they were taken, preserving the order of equal items
+        for item in kept {
+            self.put(item);
+        }
+
+        before - self.count
+    }
+
+    /// Moves every item strictly less than threshold into a new heap with the same configuration,
+    /// which is returned, leaving the rest in this heap. For example, with items keyed by time,
+    /// this pulls out everything due before now. The heap is drained and rebuilt, so this is
+    /// O(n log n).
+    pub fn extract_below(&mut self, threshold: &T) -> SkewHeap<T> {
+        let mut below = self.empty_like();
+        let items: Vec<T> = std::iter::from_fn(|| self.take()).collect();
+
+        for item in items {
+            if item < *threshold {
+                below.put(item);
+            } else {
+                self.put(item);
+            }
+        }
+
+        below
+    }
+
+    /// Consumes the heap, returning its top item along with two heaps made from the root's left
+    /// and right subtrees, e.g. for processing the two halves in parallel. The root node is freed
+    /// and the size of each new heap is recounted from its subtree.
+    pub fn split_root(mut self) -> (Option<T>, SkewHeap<T>, SkewHeap<T>) {
+        let mut left = self.empty_like();
+        let mut right = self.empty_like();
+
+        if self.root.is_null() {
+            return (None, left, right)
+        }
+
+        let root = self.root;
+        self.root = std::ptr::null_mut();
+        self.count = 0;
+
+        unsafe{
+            left.root = (*root).left;
+            right.root = (*root).right;
+            left.count = left.held();
+            right.count = right.held();
+        }
+
+        left.refresh_max();
+        right.refresh_max();
+
+        let extra = unsafe{ (*root).count - 1 };
+        let item = self.release_node(root);
+
+        // the remaining copies of an interned root item go to the left heap
+        if let Some(clone) = self.interner {
+            for _ in 0..extra {
+                left.put(clone(&item));
+            }
+        }
+
+        (Some(item), left, right)
+    }
+
+    /// Moves half of the heap's items (rounding down) into a new heap with the same
+    /// configuration, which is returned, e.g. for another worker to steal. Items are taken in
+    /// order and dealt alternately between the two heaps, so each ends up with a similar spread
+    /// of priorities.
+    pub fn split_half(&mut self) -> SkewHeap<T> {
+        let mut other = self.empty_like();
+        let items: Vec<T> = std::iter::from_fn(|| self.take()).collect();
+
+        for (i, item) in items.into_iter().enumerate() {
+            if i % 2 == 0 {
+                self.put(item);
+            } else {
+                other.put(item);
+            }
+        }
+
+        other
+    }
+
+    /// Merges another skew heap into this one, combining every run of equal items using
+    /// resolve, e.g. to sum the payloads of items sharing a key. Equality must agree with the
+    /// heap's ordering, so that equal items are taken one after another. The combined items are
+    /// drained and rebuilt, so this is O(n log n). Once merged, the other heap is destroyed. Items
+    /// are combined before this heap's capacity is checked, so only the combined items must fit.
+    /// Panics if they do not, leaving this heap empty.
+    pub fn adopt_with<F: Fn(T, T) -> T>(&mut self, other: SkewHeap<T>, resolve: F) {
+        // both heaps' items may exceed this heap's capacity until combined
+        let mut both = self.empty_like();
+        both.capacity = None;
+
+        let emptied = self.empty_like();
+        both.adopt(std::mem::replace(self, emptied));
+        both.adopt(other);
+
+        let mut items: Vec<T> = Vec::with_capacity(both.count);
+
+        while let Some(item) = both.take() {
+            match items.pop() {
+                Some(prev) if prev == item => items.push(resolve(prev, item)),
+                Some(prev) => {
+                    items.push(prev);
+                    items.push(item);
+                },
+                None => items.push(item),
+            }
+        }
+
+        self.merge_iter(items);
+    }
+
+    /// Merges a heap of another item type into this one, converting each of its items with conv,
+    /// e.g. to merge a SkewHeap<u32> into a SkewHeap<u64>. The other heap is dismantled and its
+    /// items inserted as a single batch, as by merge_iter. Panics if the merged heap would exceed
+    /// this heap's capacity.
+    pub fn adopt_converted<U: Item, F: Fn(U) -> T>(&mut self, other: SkewHeap<U>, conv: F) {
+        self.merge_iter(other.dismantle().into_iter().map(conv));
+    }
+
+    /// Consumes the heap, rebuilding its items into a heap of Reverse<T> which takes them in the
+    /// opposite order, e.g. turning a min heap into a max heap. The items are moved rather than
+    /// cloned, but the tree must be rebuilt. The heap's configuration carries over, except for a
+    /// custom comparator, interning, and the placement of incomparable items.
+    pub fn as_reversed(self) -> SkewHeap<std::cmp::Reverse<T>> {
+        let mut heap = SkewHeap::new();
+        heap.order = self.order;
+        heap.capacity = self.capacity;
+        heap.overflow = self.overflow;
+        heap.sequenced = self.sequenced;
+        heap.strategy = self.strategy;
+        heap.track_max = self.track_max;
+
+        heap.merge_iter(self.dismantle().into_iter().map(std::cmp::Reverse));
+        heap
+    }
+
+    /// Returns true if the heap contains an item equal to the one supplied
+    pub fn contains(&self, item: &T) -> bool {
+        if self.root.is_null() {
+            return false
+        }
+
+        let mut stack = vec![self.root];
+
+        while let Some(node) = stack.pop() {
+            unsafe{
+                if (*node).item == *item {
+                    return true
+                }
+
+                // Every item below a node is ordered after it, so a node that is itself ordered
+                // after the search item cannot have a matching descendant.
+                if self.after(&(*node).item, item) {
+                    continue
+                }
+
+                if !(*node).left.is_null() {
+                    stack.push((*node).left);
+                }
+
+                if !(*node).right.is_null() {
+                    stack.push((*node).right);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Consumes this heap and other, returning an iterator that alternates between taking the top
+    /// item of each, starting with this heap. Once either heap is exhausted, the iterator
+    /// continues with whatever remains in the other. Unlike a sorted merge, this enforces fairness
+    /// between the two sources rather than a global order.
+    pub fn round_robin_drain(self, other: Self) -> impl Iterator<Item = T> {
+        let mut heaps = [self, other];
+        let mut turn = 0;
+
+        std::iter::from_fn(move || {
+            for _ in 0..heaps.len() {
+                let item = heaps[turn].take();
+                turn = (turn + 1) % heaps.len();
+
+                if item.is_some() {
+                    return item
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Returns an iterator over every node's item in the heap alongside its depth in the tree,
+    /// with the root at depth 0. Items are visited depth first, starting at the root. An interned
+    /// node is visited once, no matter how many equal items it holds.
+    pub fn iter_with_depth(&self) -> impl Iterator<Item = (&T, usize)> {
+        Nodes::new(self.root).map(|(node, depth)| (&node.item, depth))
+    }
+
+    /// Folds every item in the heap (in no particular order) into an accumulator, without
+    /// modifying the heap or collecting its items. An interned item is folded in once for each
+    /// equal item its node holds.
+    pub fn fold<B, F: FnMut(B, &T) -> B>(&self, init: B, mut f: F) -> B {
+        Nodes::new(self.root).fold(init, |acc, (node, _)| {
+            (0..node.count).fold(acc, |acc, _| f(acc, &node.item))
+        })
+    }
+
+    /// Gathers a reference to every item in the heap, in depth first order, e.g. for an FFI layer
+    /// to iterate over. Nodes are not contiguous in memory, so this cannot be a true slice. An
+    /// interned item is referenced once for each equal item its node holds.
+    pub fn as_item_slice_unsorted(&self) -> Vec<&T> {
+        let mut items = Vec::with_capacity(self.count);
+
+        for (node, _) in Nodes::new(self.root) {
+            items.extend(std::iter::repeat_n(&node.item, node.count));
+        }
+
+        items
+    }
+
+    /// Returns an iterator over the items of the heap's leaf nodes, those with no children, in
+    /// depth first order
+    pub fn leaves(&self) -> impl Iterator<Item = &T> {
+        Nodes::new(self.root)
+            .filter(|(node, _)| node.left.is_null() && node.right.is_null())
+            .map(|(node, _)| &node.item)
+    }
+
+    /// Returns a reference to an item chosen uniformly at random from the heap, or None if the
+    /// heap is empty. This is a single pass of reservoir sampling over the tree. Requires the
+    /// rand feature.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        let mut chosen = None;
+        let mut seen = 0;
+
+        for (node, _) in Nodes::new(self.root) {
+            // an interned node stands for count equal items, so it is weighted accordingly
+            seen += node.count;
+
+            if rng.random_range(0..seen) < node.count {
+                chosen = Some(&node.item);
+            }
+        }
+
+        chosen
+    }
+
+    /// Returns true if the heap contains an item whose borrowed form equals q, in the manner of
+    /// HashMap::get. For example, a SkewHeap<String> may be searched for a &str without first
+    /// allocating a String. Because q need not be ordered, every item may need to be examined.
+    pub fn contains_borrow<Q>(&self, q: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        Nodes::new(self.root).any(|(node, _)| node.item.borrow() == q)
+    }
+
+    /// Panics if any node is reachable from the root by more than one path, which would mean that
+    /// the tree contains a cycle or a shared subtree, and that dropping the heap would free a node
+    /// twice. Only available in debug builds.
+    #[cfg(debug_assertions)]
+    pub fn assert_no_aliasing(&self) {
+        let mut seen = HashSet::new();
+        let mut stack = vec![];
+
+        if !self.root.is_null() {
+            stack.push(self.root);
+        }
+
+        while let Some(node) = stack.pop() {
+            assert!(seen.insert(node), "SkewHeap node {:p} is reachable by more than one path", node);
+
+            unsafe{
+                if !(*node).left.is_null() {
+                    stack.push((*node).left);
+                }
+
+                if !(*node).right.is_null() {
+                    stack.push((*node).right);
+                }
+            }
+        }
+    }
+
+    /// Returns true if the heap holds an item which cannot be ordered, such as a NaN, in which case
+    /// the heap's ordering guarantees no longer hold. Each item is checked against itself and
+    /// against the top item, which finds any NaN-like item in a single pass, but may miss an
+    /// incomparable pair in a more general partial order.
+    pub fn has_incomparable(&self) -> bool {
+        let top = match self.peek() {
+            Some(top) => top,
+            None      => return false,
+        };
+
+        Nodes::new(self.root).any(|(node, _)| {
+            node.item.partial_cmp(&node.item).is_none() || node.item.partial_cmp(top).is_none()
+        })
+    }
+
+    /// Returns true if every node is ordered no later than its children and the heap's size
+    /// agrees with the items actually held in the tree
+    pub fn is_valid(&self) -> bool {
+        let mut held = 0;
+
+        let ordered = Nodes::new(self.root).all(|(node, _)| {
+            held += node.count;
+
+            [node.left, node.right].iter()
+                .filter(|child| !child.is_null())
+                .all(|&child| unsafe{ !self.after(&node.item, &(*child).item) })
+        });
+
+        ordered && held == self.count
+    }
+
+    /// Consumes the heap, returning an iterator over its items in the order they would be taken,
+    /// which can also peek at the next item without advancing
+    pub fn into_sorted_peekable(self) -> SortedPeekable<T> {
+        SortedPeekable { heap: self }
+    }
+
+    /// Consumes the heap, returning a lazy iterator over its items in the order they would be
+    /// taken. Each call to next performs a single take, so a consumer which stops after k items
+    /// pays for only k extractions; the items left behind are freed, unsorted, when the iterator
+    /// is dropped.
+    pub fn into_sorted_stream(self) -> impl Iterator<Item = T> {
+        self.into_sorted_peekable()
+    }
+
+    /// Tears down the heap, moving every item into a vector and freeing each node as it goes.
+    /// This is a single O(n) pass and the items are returned in no particular order.
+    pub fn dismantle(mut self) -> Vec<T> {
+        let mut items = Vec::with_capacity(self.count);
+
+        if !self.root.is_null() {
+            let mut stack = vec![self.root];
+
+            while let Some(node) = stack.pop() {
+                // take ownership of the node so that it is freed once its item is moved out
+                let node = unsafe{ Box::from_raw(node) };
+                node_freed();
+
+                if !node.left.is_null() {
+                    stack.push(node.left);
+                }
+
+                if !node.right.is_null() {
+                    stack.push(node.right);
+                }
+
+                if let Some(clone) = self.interner {
+                    for _ in 1..node.count {
+                        items.push(clone(&node.item));
+                    }
+                }
+
+                items.push(node.item);
+            }
+        }
+
+        // Every node has now been freed, so drop() must not walk the tree again
+        self.root = std::ptr::null_mut();
+        self.count = 0;
+
+        items
+    }
+}
+
+impl<T: Item + std::fmt::Debug> SkewHeap<T> {
+    /// Checks the same invariants as is_valid() in a single traversal of the tree, but describes
+    /// every violation found rather than stopping at the first. This is intended for debugging
+    /// code which edits a heap's internals directly.
+    pub fn validate_detailed(&self) -> Result<(), Vec<String>> {
+        let mut violations = vec![];
+        let mut held = 0;
+
+        for (node, depth) in Nodes::new(self.root) {
+            held += node.count;
+
+            for (side, child) in [("left", node.left), ("right", node.right)].iter() {
+                if child.is_null() {
+                    continue
+                }
+
+                let child = unsafe{ &(**child).item };
+
+                if self.after(&node.item, child) {
+                    violations.push(format!(
+                        "node item {:?} at depth {} is ordered after its {} child item {:?}",
+                        node.item, depth, side, child,
+                    ));
+                }
+            }
+        }
+
+        if held != self.count {
+            violations.push(format!("count mismatch: expected {} got {}", self.count, held));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+impl<T: Item + Ord> SkewHeap<T> {
+    /// Consumes the heap, tallying the number of occurrences of each distinct item. Nodes are
+    /// freed as the heap is dismantled.
+    pub fn into_histogram(self) -> BTreeMap<T, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for item in self.dismantle() {
+            *histogram.entry(item).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// Consumes the heap, sorting its items into an immutable FrozenHeap which answers peek in
+    /// O(1) and contains in O(log n). The items are sorted by their own ordering, so a heap ordered
+    /// by a custom comparator is frozen in Min or Max order instead.
+    pub fn freeze(self) -> FrozenHeap<T> {
+        let order = self.order;
+        FrozenHeap::new(self.dismantle(), order)
+    }
+
+    /// Returns a copy of the median item, the lower of the two middle items when the size is even,
+    /// or None if the heap is empty. The item is selected from references to the heap's items in
+    /// O(n), so neither a sort nor a drain is needed and only the median itself is cloned.
+    pub fn median(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        if self.is_empty() {
+            return None
+        }
+
+        let mut items = self.as_item_slice_unsorted();
+        let middle = (items.len() - 1) / 2;
+        let (_, median, _) = items.select_nth_unstable(middle);
+        Some((*median).clone())
+    }
+
+    /// Returns true if this heap and other hold at least one equal item. One heap's items are
+    /// sorted by reference and searched for each of the other's, so neither heap is modified and
+    /// nothing is cloned.
+    pub fn intersects(&self, other: &SkewHeap<T>) -> bool {
+        let (smaller, larger) = if self.count <= other.count { (self, other) } else { (other, self) };
+
+        let mut items = smaller.as_item_slice_unsorted();
+        items.sort_unstable();
+
+        Nodes::new(larger.root).any(|(node, _)| items.binary_search(&&node.item).is_ok())
+    }
+
+    /// Returns a new heap with this heap's configuration holding copies of the items present in
+    /// both this heap and other, each as many times as it occurs in whichever holds fewer. Both
+    /// heaps' items are sorted by reference and walked together; neither heap is modified.
+    pub fn intersection(&self, other: &SkewHeap<T>) -> SkewHeap<T>
+    where
+        T: Clone,
+    {
+        let mut ours = self.as_item_slice_unsorted();
+        let mut theirs = other.as_item_slice_unsorted();
+        ours.sort_unstable();
+        theirs.sort_unstable();
+
+        let mut common = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < ours.len() && j < theirs.len() {
+            match ours[i].cmp(theirs[j]) {
+                std::cmp::Ordering::Less    => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal   => {
+                    common.push(ours[i].clone());
+                    i += 1;
+                    j += 1;
+                },
+            }
+        }
+
+        let mut heap = self.empty_like();
+        heap.merge_iter(common);
+        heap
+    }
+
+    /// Returns each distinct item in the heap alongside the number of times it occurs, in
+    /// ascending order, like into_histogram() but leaving the heap untouched. The items are
+    /// counted by reference, so only one copy of each distinct item is cloned.
+    pub fn value_distribution(&self) -> Vec<(T, usize)>
+    where
+        T: Clone,
+    {
+        let mut items = self.as_item_slice_unsorted();
+        items.sort_unstable();
+
+        let mut distribution: Vec<(T, usize)> = vec![];
+
+        for item in items {
+            match distribution.last_mut() {
+                Some((value, count)) if value == item => *count += 1,
+                _ => distribution.push((item.clone(), 1)),
+            }
+        }
+
+        distribution
+    }
+
+    /// Returns the number of distinct items in the heap, counting equal items once, unlike size().
+    /// The items are compared by reference, so nothing is cloned and the heap is left untouched.
+    pub fn distinct_count(&self) -> usize {
+        let mut items = self.as_item_slice_unsorted();
+        items.sort_unstable();
+        items.dedup();
+        items.len()
+    }
+}
+
+impl<T: Item + Copy> SkewHeap<T> {
+    /// Retrieves a copy of the top item from the heap without removing it. This was formerly
+    /// named peek.
+    pub fn peek_copied(&self) -> Option<T> {
+        self.peek().copied()
+    }
+
+    /// Removes every item from the heap in order, returning the drained items along with their
+    /// sum, totalled in the same pass. The sum is computed by T's Sum implementation, which
+    /// decides what happens on overflow; for the primitive integers, that means panicking in
+    /// debug builds and wrapping in release builds.
+    pub fn drain_sum(&mut self) -> (Vec<T>, T)
+    where
+        T: std::iter::Sum,
+    {
+        let mut items = Vec::with_capacity(self.count);
+        let sum = std::iter::from_fn(|| self.take())
+            .inspect(|item| items.push(*item))
+            .sum();
+
+        (items, sum)
+    }
+
+    /// Like drain_with_stats, but also returns the sum of the drained items, totalled in the same
+    /// pass as by drain_sum.
+    pub fn drain_with_stats_sum(&mut self) -> (Vec<T>, DrainStats<T>, T)
+    where
+        T: std::iter::Sum,
+    {
+        let (items, sum) = self.drain_sum();
+        let stats = self.stats_of(&items);
+        (items, stats, sum)
+    }
+
+    /// Returns the sum of the k items which would be taken first (or of all of them, if there are
+    /// fewer than k). Only those k items are taken, from a clone of the heap, so the heap is never
+    /// sorted in full and is itself left untouched.
+    pub fn sum_of_smallest(&self, k: usize) -> T
+    where
+        T: std::iter::Sum,
+    {
+        self.iter_sorted().take(k).sum()
+    }
+}
+
+impl<T: Item + Clone> SkewHeap<T> {
+
+    /// Removes every item from the heap in order, returning the drained items along with summary
+    /// statistics gathered along the way. The min and max are by T's own ordering, so a heap
+    /// ordered by a comparator must scan the drained items for them.
+    pub fn drain_with_stats(&mut self) -> (Vec<T>, DrainStats<T>) {
+        let mut items = Vec::with_capacity(self.count);
+
+        while let Some(item) = self.take() {
+            items.push(item);
+        }
+
+        let stats = self.stats_of(&items);
+        (items, stats)
+    }
+
+    /// Returns the statistics of items drained from this heap, in the order they were taken
+    fn stats_of(&self, items: &[T]) -> DrainStats<T> {
+        let (min, max) = if self.comparator.is_some() {
+            let min = items.iter().fold(None, |min: Option<&T>, item| match min {
+                Some(min) if item < min => Some(item),
+                None => Some(item),
+                min => min,
+            });
+
+            let max = items.iter().fold(None, |max: Option<&T>, item| match max {
+                Some(max) if item > max => Some(item),
+                None => Some(item),
+                max => max,
+            });
+
+            (min.cloned(), max.cloned())
+        } else {
+            // items are drained in heap order, so the extremes are at either end of the vector
+            let (first, last) = (items.first().cloned(), items.last().cloned());
+
+            match self.order {
+                Order::Min => (first, last),
+                Order::Max => (last, first),
+            }
+        };
+
+        DrainStats { count: items.len(), min, max }
+    }
+
+    /// Returns an iterator yielding copies of the heap's items in order. The heap is cloned once
+    /// and the clone drained one item at a time as the iterator advances, so taking only the first
+    /// few items avoids the cost of sorting the entire heap. The heap itself is left untouched.
+    pub fn iter_sorted(&self) -> impl Iterator<Item = T> {
+        let mut clone = self.clone();
+        std::iter::from_fn(move || clone.take())
+    }
+
+    /// Returns a new heap with the same configuration holding copies of the k items which would
+    /// be taken first (or all of them, if there are fewer than k), e.g. to send a capped preview
+    /// elsewhere. The heap itself is left untouched.
+    pub fn clone_smallest(&self, k: usize) -> SkewHeap<T> {
+        let mut smallest = self.empty_like();
+        smallest.extend(self.iter_sorted().take(k));
+        smallest
+    }
+
+    /// Returns copies of every item x with lo <= x <= hi, in the order they would be taken. The
+    /// tree is not ordered for searching, so every node is visited and the matches then sorted.
+    /// The heap itself is left untouched.
+    pub fn items_in_range(&self, lo: &T, hi: &T) -> Vec<T> {
+        let mut items: Vec<&T> = self.as_item_slice_unsorted().into_iter()
+            .filter(|&item| lo <= item && item <= hi)
+            .collect();
+
+        items.sort_by(|a, b| self.heap_cmp(a, b));
+        items.into_iter().cloned().collect()
+    }
+
+    /// Returns copies of the heap's items in the order they would be taken, regardless of the
+    /// shape of the tree, for comparing against an expected vector. The heap itself is left
+    /// untouched. This is the same sequence as min_trace().
+    pub fn sorted_snapshot(&self) -> Vec<T> {
+        self.min_trace()
+    }
+
+    /// Returns the sequence of items found at the root as a clone of the heap is drained, i.e.
+    /// each successive minimum (or maximum, in a max heap). The heap itself is left untouched.
+    pub fn min_trace(&self) -> Vec<T> {
+        self.iter_sorted().collect()
+    }
+
+    /// Estimates the work needed to drain the heap by draining a clone of it, counting each step
+    /// in which two non-empty subtrees are merged. The heap itself is left untouched.
+    pub fn simulate_drain_merges(&self) -> usize {
+        let merges = Cell::new(0);
+        let mut clone = self.clone();
+
+        while !clone.root.is_null() {
+            let root = clone.root;
+
+            let merged = unsafe{
+                clone.merge_by((*root).left, (*root).right, &|a, b| {
+                    merges.set(merges.get() + 1);
+                    clone.after_node(a, b)
+                })
+            };
+
+            clone.root = merged;
+            clone.count -= 1;
+            drop(clone.release_node(root));
+        }
+
+        merges.get()
+    }
+
+    /// Returns the k-th item (counting from 0) that would be taken from the heap, or None if the
+    /// heap holds k or fewer items. The heap itself is left untouched. When k is small relative
+    /// to the heap's size, this takes k + 1 items from a clone of the heap; otherwise, it performs
+    /// an average linear time quickselect over a copy of the items.
+    pub fn select_kth(&self, k: usize) -> Option<T> {
+        if k >= self.count {
+            return None
+        }
+
+        // draining costs O(k log n), against O(n) for quickselect
+        let log_n = (usize::BITS - self.count.leading_zeros()) as usize;
+
+        if k.saturating_mul(log_n) < self.count {
+            return self.iter_sorted().nth(k)
+        }
+
+        let mut items = self.clone().dismantle();
+        items.select_nth_unstable_by(k, |a, b| self.heap_cmp(a, b));
+        items.into_iter().nth(k)
+    }
+
+    /// Retrieves a copy of the top item from the heap without removing it, or an EmptyHeap error
+    /// if there is none, for callers which treat an empty heap as an error
+    pub fn try_peek(&self) -> Result<T, EmptyHeap> {
+        self.peek().cloned().ok_or(EmptyHeap)
+    }
+
+    /// Returns copies of the two items which would be taken first, without removing them, or
+    /// None if the heap holds fewer than two items. Every item below the root's children is
+    /// ordered after one of them, so the second item is always found at one of the root's
+    /// children and this is O(1).
+    pub fn peek_two(&self) -> Option<(T, T)> {
+        if self.count < 2 {
+            return None
+        }
+
+        unsafe{
+            let root = &*self.root;
+
+            // an interned root holding several equal items is its own runner up
+            if root.count > 1 {
+                return Some((root.item.clone(), root.item.clone()))
+            }
+
+            let next = match (root.left.as_ref(), root.right.as_ref()) {
+                (Some(left), Some(right)) => if self.after_node(left, right) { right } else { left },
+                (Some(child), None) | (None, Some(child)) => child,
+                (None, None) => return None,
+            };
+
+            Some((root.item.clone(), next.item.clone()))
+        }
+    }
+
+    /// Returns true if draining the heap would yield exactly the expected sequence of items. The
+    /// heap itself is left untouched; a clone is drained instead.
+    pub fn drain_equals(&self, expected: &[T]) -> bool {
+        let mut clone = self.clone();
+
+        for item in expected {
+            match clone.take() {
+                Some(taken) if taken == *item => {},
+                _ => return false,
+            }
+        }
+
+        clone.is_empty()
+    }
+
+    /// Returns copies of the items along one longest path from the root to a leaf, starting with
+    /// the root's item, or an empty vector for an empty heap. Where several paths are equally
+    /// long, the leftmost is chosen.
+    pub fn deepest_path(&self) -> Vec<T> {
+        let mut path = vec![];
+        let mut deepest = vec![];
+
+        // nodes are visited depth first, so the path to each node is the path to its parent
+        for (node, depth) in Nodes::new(self.root) {
+            path.truncate(depth);
+            path.push(node);
+
+            if path.len() > deepest.len() {
+                deepest = path.clone();
+            }
+        }
+
+        deepest.into_iter().map(|node| node.item.clone()).collect()
+    }
+
+    /// Returns a sorted copy of the heap's contents, for comparing heaps as multisets in tests.
+    /// The heap itself is left untouched.
+    #[cfg(test)]
+    fn debug_multiset(&self) -> Vec<T> {
+        let mut items = self.clone().dismantle();
+        items.sort_by(|a, b| self.heap_cmp(a, b));
+        items
+    }
+
+    /// Returns a breadth first dump of the tree's structure, in the style of a binary tree array
+    /// encoding: every node is followed in turn by its left and right children, with None marking
+    /// a missing child. Trailing None entries are omitted. An interned node appears once, no
+    /// matter how many equal items it holds.
+    pub fn to_level_order(&self) -> Vec<Option<T>> {
+        let mut items = vec![];
+        let mut queue = VecDeque::from([self.root]);
+
+        while let Some(node) = queue.pop_front() {
+            if node.is_null() {
+                items.push(None);
+                continue
+            }
+
+            unsafe{
+                items.push(Some((*node).item.clone()));
+                queue.push_back((*node).left);
+                queue.push_back((*node).right);
+            }
+        }
+
+        while let Some(None) = items.last() {
+            items.pop();
+        }
+
+        items
+    }
+
+    /// Drains clones of this heap and other side by side, returning the position of the first
+    /// item at which they differ along with each heap's item there (None once a heap has run
+    /// out), or None if both would drain identically. Neither heap is modified.
+    pub fn first_difference(&self, other: &Self) -> Option<(usize, Option<T>, Option<T>)> {
+        let mut a = self.iter_sorted();
+        let mut b = other.iter_sorted();
+
+        let mut i = 0;
+
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return None,
+                (x, y) if x != y => return Some((i, x, y)),
+                _ => i += 1,
+            }
+        }
+    }
+
+    /// Captures the current contents of the heap so that they may later be reinstated with
+    /// restore().
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot { heap: self.clone() }
+    }
+}
+
+impl<T: Item> SkewHeap<T> {
+    /// Replaces the contents of the heap with those captured by snapshot(), freeing the current
+    /// tree.
+    pub fn restore(&mut self, snapshot: Snapshot<T>) {
+        *self = snapshot.heap;
+    }
+}
+
+impl<T: Item + Clone> Clone for SkewHeap<T> {
+    /// Returns a structural copy of the heap, with every node duplicated in place
+    fn clone(&self) -> Self {
+        let mut heap = self.empty_like();
+
+        if self.root.is_null() {
+            return heap
+        }
+
+        unsafe{
+            heap.root = (*self.root).duplicate();
+            heap.count = self.count;
+
+            // Each new node is linked into the copy as soon as it is allocated, so that if an item's
+            // clone() panics, the partially built copy still frees everything when dropped.
+            let mut stack = vec![(self.root as *const Node<T>, heap.root)];
+
+            while let Some((src, dst)) = stack.pop() {
+                if !(*src).left.is_null() {
+                    (*dst).left = (*(*src).left).duplicate();
+                    stack.push(((*src).left, (*dst).left));
+                }
+
+                if !(*src).right.is_null() {
+                    (*dst).right = (*(*src).right).duplicate();
+                    stack.push(((*src).right, (*dst).right));
+                }
+            }
+        }
+
+        heap.refresh_max();
+        heap
+    }
+}
+
+/// Errors returned by SkewHeap::try_adopt when the other heap cannot be safely merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdoptError {
+    /// The two heaps are ordered differently
+    OrderMismatch,
+    /// The merged heap would exceed this heap's capacity
+    AtCapacity,
+    /// The other heap's size disagrees with the number of items held in its tree
+    Inconsistent {
+        count: usize,
+        held:  usize,
+    },
+}
+
+impl std::fmt::Display for AdoptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AdoptError::OrderMismatch => write!(f, "adopted heap has a different order"),
+            AdoptError::AtCapacity    => write!(f, "SkewHeap is at capacity"),
+            AdoptError::Inconsistent { count, held } => {
+                write!(f, "adopted heap has a size of {} but holds {} items", count, held)
+            },
+        }
+    }
+}
+
+impl std::error::Error for AdoptError {}
+
+/// The error returned by SkewHeap::try_peek when the heap is empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyHeap;
+
+impl std::fmt::Display for EmptyHeap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SkewHeap is empty")
+    }
+}
+
+impl std::error::Error for EmptyHeap {}
+
+/// Measurements of a heap's tree, as returned by SkewHeap::shape_metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShapeMetrics {
+    /// The number of items in the heap
+    pub size:                 usize,
+    /// The number of nodes along the longest path from the root to a leaf
+    pub height:               usize,
+    /// The number of nodes along the path from the root following right children
+    pub right_spine_len:      usize,
+    /// The sum of every node's depth, with the root at depth 0
+    pub internal_path_length: usize,
+}
+
+/// Summary statistics describing the items removed by SkewHeap::drain_with_stats
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrainStats<T> {
+    /// The number of items drained
+    pub count: usize,
+    /// The smallest item drained by T's own ordering, or None if the heap was empty
+    pub min:   Option<T>,
+    /// The largest item drained by T's own ordering, or None if the heap was empty
+    pub max:   Option<T>,
+}
+
+/// A saved copy of a heap's contents, produced by SkewHeap::snapshot and consumed by
+/// SkewHeap::restore.
+#[derive(Debug)]
+pub struct Snapshot<T> {
+    heap: SkewHeap<T>,
+}
+
+/// A consuming iterator over a heap's items in the order they would be taken, as returned by
+/// SkewHeap::into_sorted_peekable. The next item is always at the top of the heap, so peeking
+/// needs no lookahead buffer.
+#[derive(Debug)]
+pub struct SortedPeekable<T> {
+    heap: SkewHeap<T>,
+}
+
+impl<T: Item> SortedPeekable<T> {
+    /// Returns a reference to the next item without advancing the iterator
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+}
+
+impl<T: Item> Iterator for SortedPeekable<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.size(), Some(self.heap.size()))
+    }
+}
+
+/// Heaps are compared by their top items, with an empty heap ordered after any non-empty heap.
+/// This allows heaps to be nested inside one another, e.g. SkewHeap<SkewHeap<u32>>, ordered by
+/// each inner heap's minimum.
+impl<T: Item> PartialEq for SkewHeap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.peek() == other.peek()
+    }
+}
+
+impl<T: Item + Eq> Eq for SkewHeap<T> {}
+
+impl<T: Item> PartialOrd for SkewHeap<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self.peek(), other.peek()) {
+            (Some(a), Some(b)) => a.partial_cmp(b),
+            (a, b)             => Some(b.is_some().cmp(&a.is_some())),
+        }
+    }
+}
+
+impl<T: Item + Ord> Ord for SkewHeap<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.peek(), other.peek()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b)             => b.is_some().cmp(&a.is_some()),
+        }
+    }
+}
+
+impl<T: Item> Extend<T> for SkewHeap<T> {
+    /// Puts each item in turn
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.put(item);
+        }
+    }
+}
+
+impl<T: Item> Default for SkewHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Item + std::fmt::Display> SkewHeap<T> {
+    /// Prints out the entire tree structure for debugging
+    pub fn explain(&self) {
+        println!("SkewHeap<size={}>", self.count);
+
+        if !self.root.is_null() {
+            unsafe{ (*self.root).explain(1) }
+        }
+    }
+
+    /// Writes the tree structure as a Graphviz DOT digraph, with one node per tree node labeled
+    /// with its item, and edges to each node's children labeled L or R. Nodes are numbered in the
+    /// order they are visited, starting from the root at n0.
+    pub fn to_dot<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "digraph SkewHeap {{")?;
+
+        let mut next_id = 0;
+        let mut stack = vec![];
+
+        if !self.root.is_null() {
+            stack.push((self.root, next_id));
+            next_id += 1;
+        }
+
+        while let Some((node, id)) = stack.pop() {
+            let node = unsafe{ &*node };
+            let label = node.item.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(w, "    n{} [label=\"{}\"];", id, label)?;
+
+            for (side, child) in [("L", node.left), ("R", node.right)].iter() {
+                if !child.is_null() {
+                    writeln!(w, "    n{} -> n{} [label=\"{}\"];", id, next_id, side)?;
+                    stack.push((*child, next_id));
+                    next_id += 1;
+                }
+            }
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+impl<T> Drop for SkewHeap<T> {
+    fn drop(&mut self) {
+        // pooled nodes hold no item, so they are freed without dropping one
+        for node in self.pool.drain(..) {
+            node_freed();
+            unsafe{ drop(Box::from_raw(node as *mut MaybeUninit<Node<T>>)) }
+        }
+
+        if !self.root.is_null() {
+            drop(FreeNodes(vec![self.root]));
+        }
+    }
+}
+
+/// Frees the nodes it holds along with all of their descendants when dropped. Should an item's
+/// drop panic, the nodes not yet freed are handed to a new FreeNodes which frees them as the panic
+/// unwinds, so that one bad item does not leak the rest of the tree.
+struct FreeNodes<T>(Vec<*mut Node<T>>);
+
+impl<T> Drop for FreeNodes<T> {
+    fn drop(&mut self) {
+        while let Some(node) = self.0.pop() {
+            unsafe{
+                if !(*node).left.is_null() {
+                    self.0.push((*node).left);
+                }
+
+                if !(*node).right.is_null() {
+                    self.0.push((*node).right);
+                }
+
+                let mut rest = FreeNodes(std::mem::take(&mut self.0));
+                node_freed();
+                drop(Box::from_raw(node));
+                self.0 = std::mem::take(&mut rest.0);
+            }
+        }
+    }
+}
+
+/// A tree shape written out as a nested literal, from which tests may build a heap of an exact
+/// configuration with SkewHeap::from_structure
+#[cfg(test)]
+enum Shape<T> {
+    Empty,
+    Node(T, Box<Shape<T>>, Box<Shape<T>>),
+}
+
+#[cfg(test)]
+impl<T> Shape<T> {
+    fn leaf(item: T) -> Self {
+        Shape::Node(item, Box::new(Shape::Empty), Box::new(Shape::Empty))
+    }
+
+    fn node(item: T, left: Shape<T>, right: Shape<T>) -> Self {
+        Shape::Node(item, Box::new(left), Box::new(right))
+    }
+}
+
+#[cfg(test)]
+impl<T: Item> SkewHeap<T> {
+    /// Builds a heap with exactly the given tree shape. The shape is not checked against the heap
+    /// property, so tests may also construct invalid heaps.
+    fn from_structure(shape: Shape<T>) -> Self {
+        let mut heap = Self::new();
+        let mut stack = vec![(shape, &mut heap.root as *mut *mut Node<T>)];
+
+        while let Some((shape, slot)) = stack.pop() {
+            if let Shape::Node(item, left, right) = shape {
+                let node = heap.alloc_node(item);
+                heap.count += 1;
+
+                unsafe{
+                    *slot = node;
+                    stack.push((*left, &mut (*node).left));
+                    stack.push((*right, &mut (*node).right));
+                }
+            }
+        }
+
+        heap
+    }
+
+    /// Returns true if every node in the subtree rooted at node is ordered no later than its
+    /// children. A null subtree is valid. No path through this heap's tree can hold more nodes
+    /// than the heap's size, so a deeper path (as left by a cycle) fails the check rather than
+    /// running forever.
+    fn node_is_valid(&self, node: *mut Node<T>) -> bool {
+        Nodes::new(node).all(|(node, depth)| {
+            depth < self.count && [node.left, node.right].iter()
+                .filter(|child| !child.is_null())
+                .all(|&child| unsafe{ !self.after(&node.item, &(*child).item) })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdoptError, CompareFn, DrainStats, EmptyHeap, MergeStrategy, Nodes, Order, Prioritized, Shape, ShapeMetrics, SkewHeap};
+
+    use std::collections::HashSet;
+
+    use rand::rng;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_positive_path() {
+        let mut skew = SkewHeap::new();
+
+        assert!(skew.is_empty(), "initially empty");
+        assert_eq!(skew.peek(), None, "peek returns None when is_empty");
+        assert_eq!(skew.take(), None, "take returns None when is_empty");
+
+        assert_eq!(skew.put(10), 1, "put returns new size");
+        assert_eq!(skew.peek(), Some(&10), "peek returns top entry after put");
+        assert_eq!(skew.size(), 1, "size returns expected count after put");
+        assert!(!skew.is_empty(), "is_empty false after put");
+
+        assert_eq!(skew.put(3), 2, "put returns new size");
+        assert_eq!(skew.peek(), Some(&3), "peek returns top entry after put");
+        assert_eq!(skew.size(), 2, "size returns expected count after put");
+        assert!(!skew.is_empty(), "is_empty false after put");
+
+        assert_eq!(skew.put(15), 3, "put returns new size");
+        assert_eq!(skew.peek(), Some(&3), "peak returns top entry after put");
+        assert_eq!(skew.size(), 3, "size returns expected count after put");
+        assert!(!skew.is_empty(), "is_empty false after put");
+
+        assert_eq!(skew.take(), Some(3), "take returns top entry");
+        assert_eq!(skew.peek(), Some(&10), "peek returns top entry after take");
+        assert_eq!(skew.size(), 2, "size returns expected count after take");
+        assert!(!skew.is_empty(), "is_empty false when > 0 entries");
+
+        assert_eq!(skew.take(), Some(10), "take returns top entry");
+        assert_eq!(skew.peek(), Some(&15), "peek returns top entry after take");
+        assert_eq!(skew.size(), 1, "size returns expected count after take");
+        assert!(!skew.is_empty(), "is_empty false when > 0 entries");
+
+        assert_eq!(skew.take(), Some(15), "take returns top entry");
+        assert_eq!(skew.peek(), None, "peek returns None after final entry returned by take");
+        assert_eq!(skew.size(), 0, "size is 0 after final entry returned by take");
+        assert!(skew.is_empty(), "is_empty true after final entry returned by take");
+    }
+
+    #[test]
+    fn test_merge_heaps() {
+        let mut a = SkewHeap::new();
+        a.put(1);
+        a.put(2);
+        a.put(3);
+
+        let mut b = SkewHeap::new();
+        b.put(4);
+        b.put(5);
+        b.put(6);
+
+        a.adopt(b);
+        assert_eq!(a.size(), 6);
+        assert_eq!(a.take(), Some(1));
+        assert_eq!(a.take(), Some(2));
+        assert_eq!(a.take(), Some(3));
+        assert_eq!(a.take(), Some(4));
+        assert_eq!(a.take(), Some(5));
+        assert_eq!(a.take(), Some(6));
+    }
+
+    #[test]
+    fn test_large_randomized_input() {
+        let size = 1000;
+
+        let mut items: Vec<u32> = (0..size).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        for n in 0..size {
+            assert_eq!(skew.take(), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_dismantle() {
+        let mut items: Vec<u32> = (0..100).chain(0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in &items {
+            skew.put(*n);
+        }
+
+        let mut dismantled = skew.dismantle();
+        assert_eq!(dismantled.len(), items.len(), "dismantle returns every item");
+
+        dismantled.sort();
+        items.sort();
+        assert_eq!(dismantled, items, "dismantle returns the inserted multiset");
+
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert!(empty.dismantle().is_empty(), "dismantle on an empty heap returns no items");
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut skew = SkewHeap::new();
+        assert!(!skew.contains(&1), "empty heap contains nothing");
+
+        for n in [5, 3, 8, 1, 9] {
+            skew.put(n);
+        }
+
+        for n in [5, 3, 8, 1, 9] {
+            assert!(skew.contains(&n), "contains {}", n);
+        }
+
+        for n in [0, 2, 4, 10] {
+            assert!(!skew.contains(&n), "does not contain {}", n);
+        }
+    }
+
+    #[test]
+    fn test_adopt_dedup() {
+        let mut a = SkewHeap::new();
+        for n in [1, 2, 3, 4] {
+            a.put(n);
+        }
+
+        let mut b = SkewHeap::new();
+        for n in [3, 4, 5, 6] {
+            b.put(n);
+        }
+
+        a.adopt_dedup(b);
+        assert_eq!(a.size(), 6, "duplicates from other are not counted");
+
+        for n in 1..=6 {
+            assert_eq!(a.take(), Some(n));
+        }
+
+        assert_eq!(a.take(), None, "duplicates from other were dropped");
+    }
+
+    #[test]
+    fn test_iter_with_depth() {
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert_eq!(empty.iter_with_depth().count(), 0, "empty heap yields nothing");
+
+        let mut skew = SkewHeap::new();
+        for n in [1, 2, 3] {
+            skew.put(n);
+        }
+
+        // 2 is merged below 1, then 3 merges into 1's empty right child, rotating 2 to the right
+        let nodes: Vec<(u32, usize)> = skew.iter_with_depth()
+            .map(|(item, depth)| (*item, depth))
+            .collect();
+
+        assert_eq!(nodes[0], (1, 0), "root is reported first at depth 0");
+        assert_eq!(nodes, vec![(1, 0), (3, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut a = SkewHeap::new();
+        for n in [5, 1, 4, 2, 3] {
+            a.put(n);
+        }
+
+        let mut b = a.clone();
+        assert_eq!(b.size(), a.size(), "clone has the same size");
+
+        let shape_a: Vec<(u32, usize)> = a.iter_with_depth().map(|(n, d)| (*n, d)).collect();
+        let shape_b: Vec<(u32, usize)> = b.iter_with_depth().map(|(n, d)| (*n, d)).collect();
+        assert_eq!(shape_a, shape_b, "clone has the same structure");
+
+        b.put(0);
+        assert_eq!(b.take(), Some(0), "clone is independent of original");
+        assert_eq!(a.size(), 5, "original is untouched by changes to clone");
+
+        for n in 1..=5 {
+            assert_eq!(a.take(), Some(n));
+            assert_eq!(b.take(), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut skew = SkewHeap::new();
+        for n in [3, 1, 2] {
+            skew.put(n);
+        }
+
+        let snapshot = skew.snapshot();
+
+        skew.take();
+        skew.put(10);
+        skew.put(20);
+        assert_eq!(skew.size(), 4);
+
+        skew.restore(snapshot);
+        assert_eq!(skew.size(), 3, "restore reinstates the original size");
+        assert_eq!(skew.take(), Some(1));
+        assert_eq!(skew.take(), Some(2));
+        assert_eq!(skew.take(), Some(3));
+        assert_eq!(skew.take(), None, "restore discards items added after the snapshot");
+    }
+
+    #[test]
+    fn test_partial_none_last() {
+        let mut skew = SkewHeap::new_partial_none_last();
+        for n in [3.0, f64::NAN, 1.0, f64::NAN, 2.0, 0.5] {
+            skew.put(n);
+        }
+
+        assert_eq!(skew.take(), Some(0.5));
+        assert_eq!(skew.take(), Some(1.0));
+        assert_eq!(skew.take(), Some(2.0));
+        assert_eq!(skew.take(), Some(3.0));
+        assert!(skew.take().unwrap().is_nan(), "NaN is taken after all finite values");
+        assert!(skew.take().unwrap().is_nan(), "NaN is taken after all finite values");
+        assert_eq!(skew.take(), None);
+    }
+
+    #[test]
+    fn test_round_robin_drain() {
+        let mut a = SkewHeap::new();
+        for n in [1, 2, 3, 4, 5] {
+            a.put(n);
+        }
+
+        let mut b = SkewHeap::new();
+        for n in [10, 20] {
+            b.put(n);
+        }
+
+        let drained: Vec<u32> = a.round_robin_drain(b).collect();
+        assert_eq!(drained, vec![1, 10, 2, 20, 3, 4, 5], "alternates until one heap is exhausted");
+    }
+
+    #[test]
+    fn test_drain_with_stats() {
+        let mut skew = SkewHeap::new();
+        for n in [7, 3, 9, 1, 5] {
+            skew.put(n);
+        }
+
+        let (items, stats) = skew.drain_with_stats();
+        assert_eq!(items, vec![1, 3, 5, 7, 9], "items are drained in order");
+        assert_eq!(stats, DrainStats { count: 5, min: Some(1), max: Some(9) });
+        assert!(skew.is_empty(), "heap is empty after drain");
+
+        let (items, stats) = skew.drain_with_stats();
+        assert!(items.is_empty());
+        assert_eq!(stats, DrainStats { count: 0, min: None, max: None }, "stats for empty heap");
+    }
+
+    #[test]
+    fn test_drain_with_stats_max_order() {
+        let mut skew = SkewHeap::builder().order(Order::Max).build().unwrap();
+        for n in [7, 3, 9] {
+            skew.put(n);
+        }
+
+        let (items, stats) = skew.drain_with_stats();
+        assert_eq!(items, vec![9, 7, 3]);
+        assert_eq!(stats, DrainStats { count: 3, min: Some(3), max: Some(9) });
+    }
+
+    #[test]
+    fn test_drain_with_stats_comparator() {
+        let mut skew = SkewHeap::new_with_comparator(Box::new(|a: &u32, b: &u32| b.cmp(a)));
+        skew.extend(vec![1, 9, 5]);
+
+        let (items, stats) = skew.drain_with_stats();
+        assert_eq!(items, vec![9, 5, 1], "items are drained in the comparator's order");
+        assert_eq!(stats, DrainStats { count: 3, min: Some(1), max: Some(9) }, "min and max by value");
+    }
+
+    #[test]
+    fn test_drain_with_stats_sum() {
+        let mut skew = SkewHeap::heapify(vec![7, 3, 9, 1, 5]);
+
+        let (items, stats, sum) = skew.drain_with_stats_sum();
+        assert_eq!(items, vec![1, 3, 5, 7, 9], "items are drained in order");
+        assert_eq!(stats, DrainStats { count: 5, min: Some(1), max: Some(9) });
+        assert_eq!(sum, items.iter().sum::<u32>(), "sum matches a second pass");
+
+        let (items, stats, sum) = skew.drain_with_stats_sum();
+        assert!(items.is_empty());
+        assert_eq!((stats.count, sum), (0, 0), "stats for empty heap");
+    }
+
+    #[test]
+    fn test_heapify() {
+        let size = 1000;
+
+        let mut items: Vec<u32> = (0..size).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::heapify(items);
+        assert_eq!(skew.size(), size as usize, "heapify counts every item");
+
+        for n in 0..size {
+            assert_eq!(skew.take(), Some(n));
+        }
+
+        let empty: SkewHeap<u32> = SkewHeap::heapify(vec![]);
+        assert!(empty.is_empty(), "heapify of no items is empty");
+    }
+
+    #[test]
+    fn test_peek_through_trait_object() {
+        trait PriorityQueue<T> {
+            fn peek(&self) -> Option<&T>;
+            fn push(&mut self, item: T);
+        }
+
+        impl PriorityQueue<u32> for SkewHeap<u32> {
+            fn peek(&self) -> Option<&u32> {
+                SkewHeap::peek(self)
+            }
+
+            fn push(&mut self, item: u32) {
+                self.put(item);
+            }
+        }
+
+        let mut skew = SkewHeap::new();
+        let queue: &mut dyn PriorityQueue<u32> = &mut skew;
+        assert_eq!(queue.peek(), None, "peek returns None when empty");
+
+        queue.push(5);
+        queue.push(2);
+        assert_eq!(queue.peek(), Some(&2), "peek returns reference to top item");
+
+        assert_eq!(skew.peek_copied(), Some(2), "peek_copied returns a copy of the top item");
+        assert_eq!(skew.size(), 2, "peeking does not remove items");
+    }
+
+    #[test]
+    fn test_drain_equals() {
+        // Ordered by priority alone, but equal only when both fields match, so that the drain
+        // order of equal-priority tasks is observable.
+        #[derive(Debug, Clone, PartialEq)]
+        struct Task(u32, char);
+
+        impl PartialOrd for Task {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let mut skew = SkewHeap::new();
+        for task in [Task(1, 'a'), Task(1, 'b'), Task(1, 'c'), Task(0, 'z'), Task(1, 'd')] {
+            skew.put(task);
+        }
+
+        let expected = [Task(0, 'z'), Task(1, 'd'), Task(1, 'a'), Task(1, 'c'), Task(1, 'b')];
+        assert!(skew.drain_equals(&expected), "equal items drain in a reproducible order");
+        assert!(!skew.drain_equals(&expected[..4]), "false when items would remain");
+        assert!(!skew.drain_equals(&[Task(0, 'z')]), "false when sequence differs");
+        assert_eq!(skew.size(), 5, "heap is untouched");
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        let smallest: Vec<u32> = skew.iter_sorted().take(3).collect();
+        assert_eq!(smallest, vec![0, 1, 2], "yields the smallest items in order");
+        assert_eq!(skew.size(), 100, "heap is untouched");
+        assert_eq!(skew.peek(), Some(&0), "heap is untouched");
+        assert!(skew.iter_sorted().eq(0..100), "yields every item in order");
+    }
+
+    #[test]
+    fn test_heap_ordering() {
+        let heap_of = |items: &[u32]| {
+            let mut heap = SkewHeap::new();
+            for n in items {
+                heap.put(*n);
+            }
+            heap
+        };
+
+        assert!(heap_of(&[1, 9]) < heap_of(&[2, 3]), "ordered by minimum");
+        assert!(heap_of(&[2, 9]) == heap_of(&[2, 3]), "equal when minimums are equal");
+        assert!(heap_of(&[]) > heap_of(&[100]), "empty heap sorts greatest");
+        assert_eq!(heap_of(&[]).cmp(&heap_of(&[])), std::cmp::Ordering::Equal);
+
+        let mut outer = SkewHeap::new();
+        outer.put(heap_of(&[5, 6]));
+        outer.put(heap_of(&[]));
+        outer.put(heap_of(&[3, 8]));
+        outer.put(heap_of(&[4]));
+
+        let minimums: Vec<Option<u32>> = std::iter::from_fn(|| outer.take())
+            .map(|inner| inner.peek_copied())
+            .collect();
+
+        assert_eq!(minimums, vec![Some(3), Some(4), Some(5), None], "outer heap ordered by minimums");
+    }
+
+    #[test]
+    fn test_reset_for_reuse() {
+        let mut skew = SkewHeap::new();
+
+        for _ in 0..3 {
+            for n in [3, 1, 2] {
+                skew.put(String::from("item") + &n.to_string());
+            }
+
+            skew.reset_for_reuse();
+            assert!(skew.is_empty(), "empty after reset_for_reuse");
+            assert_eq!(skew.size(), 0, "size is 0 after reset_for_reuse");
+            assert_eq!(skew.pool.len(), 3, "freed nodes are kept for reuse");
+        }
+
+        skew.put(String::from("b"));
+        skew.put(String::from("a"));
+        assert_eq!(skew.pool.len(), 1, "put reuses pooled nodes");
+
+        assert_eq!(skew.take(), Some(String::from("a")));
+        assert_eq!(skew.pool.len(), 1, "take frees its node unless recycling");
+        assert_eq!(skew.take(), Some(String::from("b")));
+        assert_eq!(skew.take(), None);
+    }
+
+    #[test]
+    fn test_contains_borrow() {
+        let mut skew = SkewHeap::new();
+        assert!(!skew.contains_borrow("a"), "empty heap contains nothing");
+
+        for name in ["pear", "apple", "fig"] {
+            skew.put(String::from(name));
+        }
+
+        assert!(skew.contains_borrow("apple"), "finds &str in heap of String");
+        assert!(skew.contains_borrow("fig"), "finds &str in heap of String");
+        assert!(skew.contains_borrow("pear"), "finds &str in heap of String");
+        assert!(!skew.contains_borrow("plum"), "does not find missing &str");
+    }
+
+    #[test]
+    fn test_drain_each() {
+        let mut items: Vec<u64> = (1..=100).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        let mut sum = 0;
+        let mut last = 0;
+
+        skew.drain_each(|n| {
+            assert!(n > last, "items are passed in order");
+            last = n;
+            sum += n;
+        });
+
+        assert_eq!(sum, 5050, "every item is passed to the callback");
+        assert_eq!(skew.size(), 0, "size is 0 after drain_each");
+        assert!(skew.is_empty(), "heap is empty after drain_each");
+    }
+
+    #[test]
+    fn test_simulate_drain_merges() {
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert_eq!(empty.simulate_drain_merges(), 0, "no merges to drain an empty heap");
+
+        let mut skew = SkewHeap::new();
+        for n in [5, 3, 8, 1, 9, 2, 7] {
+            skew.put(n);
+        }
+
+        let shape: Vec<(u32, usize)> = skew.iter_with_depth().map(|(n, d)| (*n, d)).collect();
+
+        assert!(skew.simulate_drain_merges() > 0, "draining a multi-item heap requires merges");
+        assert_eq!(skew.size(), 7, "heap is untouched");
+
+        let after: Vec<(u32, usize)> = skew.iter_with_depth().map(|(n, d)| (*n, d)).collect();
+        assert_eq!(shape, after, "heap structure is untouched");
+    }
+
+    #[test]
+    fn test_for_each_mut() {
+        let mut skew = SkewHeap::new();
+        for n in [5, 1, 4, 2, 3] {
+            skew.put(n);
+        }
+
+        skew.for_each_mut(|n| *n *= 2);
+        assert_eq!(skew.size(), 5, "size is unchanged");
+
+        for n in [2, 4, 6, 8, 10] {
+            assert_eq!(skew.take(), Some(n));
+        }
+
+        for n in 0..10 {
+            skew.put(n);
+        }
+
+        // reverse the ordering so the heap must be rebuilt to remain valid
+        skew.for_each_mut(|n| *n = 100 - *n);
+        for n in 91..=100 {
+            assert_eq!(skew.take(), Some(n), "heap order restored after mutation");
+        }
+    }
+
+    #[test]
+    fn test_bounded_union() {
+        let heap_of = |items: &[u32]| {
+            let mut heap = SkewHeap::new();
+            for n in items {
+                heap.put(*n);
+            }
+            heap
+        };
+
+        let heaps = vec![heap_of(&[9, 1, 5]), heap_of(&[2, 8]), heap_of(&[7, 3, 6, 4])];
+        let mut union = SkewHeap::bounded_union(heaps, 4);
+
+        assert_eq!(union.size(), 4, "size is bounded by cap");
+        for n in 1..=4 {
+            assert_eq!(union.take(), Some(n), "smallest items are retained");
+        }
+        assert_eq!(union.take(), None);
+
+        let union = SkewHeap::bounded_union(vec![heap_of(&[1, 2]), heap_of(&[3])], 10);
+        assert_eq!(union.size(), 3, "size is the total when below cap");
+
+        let union: SkewHeap<u32> = SkewHeap::bounded_union(vec![], 10);
+        assert!(union.is_empty(), "union of no heaps is empty");
+    }
+
+    #[test]
+    fn test_drain_grouped() {
+        let mut skew = SkewHeap::new();
+        for n in [3, 1, 2, 3, 1, 3, 5] {
+            skew.put(n);
+        }
+
+        let groups: Vec<(u32, usize)> = skew.drain_grouped().collect();
+        assert_eq!(groups, vec![(1, 2), (2, 1), (3, 3), (5, 1)], "equal runs are coalesced");
+        assert_eq!(groups.iter().map(|(_, run)| run).sum::<usize>(), 7, "runs sum to original size");
+        assert!(skew.is_empty(), "heap is drained");
+    }
+
+    #[test]
+    fn test_select_kth() {
+        let size = 1000;
+
+        let mut items: Vec<u32> = (0..size).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        assert_eq!(skew.select_kth(0), Some(0), "k = 0 is the top item");
+        assert_eq!(skew.select_kth(5), Some(5), "small k");
+        assert_eq!(skew.select_kth(700), Some(700), "large k");
+        assert_eq!(skew.select_kth(size as usize - 1), Some(size - 1), "last item");
+        assert_eq!(skew.select_kth(size as usize), None, "out of range");
+        assert_eq!(skew.size(), size as usize, "heap is untouched");
+
+        let mut max = SkewHeap::builder().order(Order::Max).build().unwrap();
+        for n in 0..100 {
+            max.put(n);
+        }
+
+        assert_eq!(max.select_kth(2), Some(97), "follows the heap's order for small k");
+        assert_eq!(max.select_kth(90), Some(9), "follows the heap's order for large k");
+    }
+
+    #[test]
+    fn test_cycle_min() {
+        let mut skew = SkewHeap::new();
+        assert!(skew.cycle_min().is_none(), "cycle_min returns None when empty");
+
+        for name in ["a", "b", "c"] {
+            skew.put(Prioritized::new(1, name));
+        }
+        skew.put(Prioritized::new(2, "z"));
+
+        assert_eq!(skew.cycle_min().map(|p| p.value), Some("a"), "returns the cycled item");
+        assert_ne!(skew.peek().map(|p| p.value), Some("a"), "cycled item goes after its equals");
+        assert_eq!(skew.size(), 4, "size is unchanged");
+
+        let mut sequenced = SkewHeap::builder().sequenced().build().unwrap();
+        for name in ["a", "b", "c"] {
+            sequenced.put(Prioritized::new(1, name));
+        }
+        sequenced.put(Prioritized::new(2, "z"));
+
+        let cycled: Vec<&str> = (0..7).map(|_| sequenced.cycle_min().unwrap().value).collect();
+        assert_eq!(cycled, vec!["a", "b", "c", "a", "b", "c", "a"], "rotates through equal items");
+        assert_eq!(sequenced.size(), 4, "size is unchanged");
+    }
+
+    #[test]
+    fn test_cycle_min_interning() {
+        let mut skew = SkewHeap::builder().interning().build().unwrap();
+        skew.extend(vec![1, 1, 1, 2]);
+        let nodes = Nodes::new(skew.root).count();
+
+        for _ in 0..5 {
+            assert_eq!(skew.cycle_min(), Some(&1), "returns the cycled item");
+        }
+
+        assert!(skew.is_valid());
+        assert_eq!(skew.size(), 4, "size is unchanged");
+        assert_eq!(Nodes::new(skew.root).count(), nodes, "cycled item rejoins its interned node");
+        assert_eq!(std::iter::from_fn(|| skew.take()).collect::<Vec<_>>(), vec![1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_cycle_min_max_tracking() {
+        let mut skew = SkewHeap::with_max_tracking();
+        skew.put(5);
+        assert_eq!(skew.cycle_min(), Some(&5));
+        assert_eq!(skew.peek_max(), Some(&5), "the only item is still the max once cycled");
+
+        skew.extend(vec![3, 9, 3]);
+        for _ in 0..4 {
+            skew.cycle_min();
+            assert_eq!(skew.peek_max(), Some(&9), "cycling the min leaves the max in place");
+        }
+
+        let mut equal = SkewHeap::with_max_tracking();
+        for name in ["a", "b"] {
+            equal.put(Prioritized::new(1, name));
+        }
+
+        for _ in 0..4 {
+            equal.cycle_min();
+            assert_eq!(equal.peek_max().map(|p| p.priority), Some(1), "max is tracked across cycled nodes");
+            assert!(equal.is_valid());
+        }
+
+        assert_eq!(equal.size(), 2, "size is unchanged");
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let mut skew = SkewHeap::new();
+        for n in 0..10 {
+            skew.put(n);
+        }
+
+        let cancelled: HashSet<u32> = [2, 5, 8, 42].iter().copied().collect();
+        assert_eq!(skew.remove_all(&cancelled), 3, "returns number of items removed");
+        assert_eq!(skew.size(), 7, "size reflects remaining items");
+        assert!(skew.drain_equals(&[0, 1, 3, 4, 6, 7, 9]), "remaining items are intact");
+    }
+
+    #[test]
+    fn test_adopt_preserves_multisets() {
+        let mut a = SkewHeap::new();
+        let mut b = SkewHeap::new();
+        for n in [5, 1, 3, 3, 8] {
+            a.put(n);
+        }
+        for n in [3, 2, 8, 0] {
+            b.put(n);
+        }
+
+        let mut expected = a.debug_multiset();
+        expected.extend(b.debug_multiset());
+        expected.sort_unstable();
+
+        assert_eq!(a.debug_multiset(), vec![1, 3, 3, 5, 8], "debug_multiset leaves heap intact");
+        a.adopt(b);
+        assert_eq!(a.debug_multiset(), expected, "adopted heap holds both inputs' items");
+    }
+
+    #[test]
+    fn test_leaves() {
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.leaves().count(), 0, "empty heap has no leaves");
+
+        skew.put(1);
+        assert_eq!(skew.leaves().collect::<Vec<_>>(), vec![&1], "lone root is a leaf");
+
+        // 1 -> (2 -> (4), 3)
+        for n in 2..=4 {
+            skew.put(n);
+        }
+
+        let mut leaves: Vec<u32> = skew.leaves().copied().collect();
+        leaves.sort_unstable();
+        assert_eq!(leaves, vec![3, 4], "yields only childless nodes");
+    }
+
+    #[test]
+    fn test_to_level_order() {
+        let skew: SkewHeap<u32> = SkewHeap::new();
+        assert!(skew.to_level_order().is_empty(), "empty heap encodes to nothing");
+
+        // 1 -> (3 -> (5), 2 -> (4))
+        let mut skew = SkewHeap::new();
+        for n in 1..=5 {
+            skew.put(n);
+        }
+
+        assert_eq!(
+            skew.to_level_order(),
+            vec![Some(1), Some(3), Some(2), Some(5), None, Some(4)],
+            "encodes tree breadth first with missing children as None",
+        );
+    }
+
+    #[test]
+    fn test_from_level_order() {
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        let encoded = skew.to_level_order();
+        let mut decoded = SkewHeap::from_level_order(encoded.clone());
+        assert_eq!(decoded.size(), 100, "size is the number of Some entries");
+        assert_eq!(decoded.to_level_order(), encoded, "tree structure is restored exactly");
+
+        let original: Vec<u32> = std::iter::from_fn(|| skew.take()).collect();
+        let restored: Vec<u32> = std::iter::from_fn(|| decoded.take()).collect();
+        assert_eq!(restored, original, "drains identically to the original");
+
+        let mut invalid = SkewHeap::from_level_order(vec![Some(5), Some(1), None, Some(3)]);
+        assert_eq!(invalid.size(), 3, "size is the number of Some entries");
+        assert_eq!(invalid.take(), Some(1), "rebuilds a heap violating the heap property");
+        assert_eq!(invalid.take(), Some(3));
+        assert_eq!(invalid.take(), Some(5));
+
+        let mut orphaned = SkewHeap::from_level_order(vec![None, Some(2), Some(1)]);
+        assert_eq!(orphaned.size(), 2, "entries without a parent are kept");
+        assert_eq!(orphaned.take(), Some(1));
+        assert_eq!(orphaned.take(), Some(2));
+    }
+
+    #[test]
+    fn test_put_max_hint() {
+        let mut hinted = SkewHeap::new();
+        let mut skew = SkewHeap::new();
+
+        for n in 0..100 {
+            assert_eq!(hinted.put_max_hint(n), n as usize + 1, "put_max_hint returns new size");
+            skew.put(n);
+        }
+
+        assert_eq!(hinted.to_level_order(), skew.to_level_order(), "builds the same tree as put");
+        assert!(hinted.drain_equals(&(0..100).collect::<Vec<_>>()), "items are taken in order");
+    }
+
+    #[test]
+    fn test_split_half() {
+        let mut skew = SkewHeap::new();
+        for n in 0..10 {
+            skew.put(n);
+        }
+
+        let mut other = skew.split_half();
+        assert_eq!(skew.size(), 5, "half the items remain");
+        assert_eq!(other.size(), 5, "half the items are moved");
+
+        let mut items = skew.debug_multiset();
+        items.extend(other.debug_multiset());
+        items.sort_unstable();
+        assert_eq!(items, (0..10).collect::<Vec<_>>(), "no items are lost or duplicated");
+
+        skew.put(10);
+        let odd = skew.split_half();
+        assert_eq!((skew.size(), odd.size()), (3, 3), "sizes sum to the original");
+        assert_eq!(other.split_half().size(), 2, "rounds down");
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.sample(&mut rng), None, "sample returns None when empty");
+
+        for n in 0..10 {
+            skew.put(n);
+        }
+
+        let trials = 10_000;
+        let mut hits = [0; 10];
+
+        for _ in 0..trials {
+            hits[*skew.sample(&mut rng).unwrap()] += 1;
+        }
+
+        for (n, count) in hits.iter().enumerate() {
+            assert!((800..1200).contains(count), "item {} sampled {} times of {}", n, count, trials);
+        }
+
+        assert_eq!(skew.size(), 10, "heap is untouched");
+    }
+
+    #[test]
+    fn test_merged() {
         let mut a = SkewHeap::new();
-        a.put(1);
-        a.put(2);
-        a.put(3);
+        let mut b = SkewHeap::new();
+        for n in [4, 0, 2] {
+            a.put(n);
+        }
+        for n in [3, 1, 5] {
+            b.put(n);
+        }
+
+        let merged = a.merged(b);
+        assert_eq!(merged.size(), 6, "size is the sum of both heaps");
+        assert!(merged.drain_equals(&[0, 1, 2, 3, 4, 5]), "holds the items of both heaps");
+        assert!(SkewHeap::<u32>::new().merged(SkewHeap::new()).is_empty(), "merging empty heaps");
+    }
+
+    #[test]
+    fn test_validate_detailed() {
+        // 1 -> (3, 2)
+        let mut skew = SkewHeap::new();
+        for n in 1..=3 {
+            skew.put(n);
+        }
+
+        assert!(skew.is_valid(), "heap is initially valid");
+        assert_eq!(skew.validate_detailed(), Ok(()), "no violations reported");
+
+        unsafe{ (*skew.root).item = 5 }
+        skew.count = 7;
+
+        assert!(!skew.is_valid(), "corrupted heap is invalid");
+        assert_eq!(skew.validate_detailed(), Err(vec![
+            String::from("node item 5 at depth 0 is ordered after its left child item 3"),
+            String::from("node item 5 at depth 0 is ordered after its right child item 2"),
+            String::from("count mismatch: expected 7 got 3"),
+        ]), "every violation is reported");
+    }
+
+    #[test]
+    fn test_into_histogram() {
+        let mut skew = SkewHeap::new();
+        for n in [3, 1, 3, 2, 3, 1] {
+            skew.put(n);
+        }
+
+        let histogram: Vec<(u32, usize)> = skew.into_histogram().into_iter().collect();
+        assert_eq!(histogram, vec![(1, 2), (2, 1), (3, 3)], "counts each distinct item");
+        assert!(SkewHeap::<u32>::new().into_histogram().is_empty(), "empty heap has no counts");
+    }
+
+    #[test]
+    fn test_peek_two() {
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.peek_two(), None, "None when empty");
+
+        skew.put(45);
+        assert_eq!(skew.peek_two(), None, "None with a single item");
+
+        // symbol weights from the classic Huffman coding example
+        for weight in [13, 12, 16, 9, 5] {
+            skew.put(weight);
+        }
+
+        assert_eq!(skew.peek_two(), Some((5, 9)), "returns the two smallest items");
+        assert_eq!(skew.size(), 6, "heap is untouched");
+
+        skew.take();
+        skew.take();
+        assert_eq!(skew.peek_two(), Some((12, 13)), "returns the two smallest after takes");
+    }
+
+    #[test]
+    fn test_split_root() {
+        let (root, left, right) = SkewHeap::<u32>::new().split_root();
+        assert_eq!(root, None, "empty heap has no root");
+        assert!(left.is_empty() && right.is_empty(), "empty heap splits into empty heaps");
+
+        let mut items: Vec<u32> = (0..20).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        let (root, left, right) = skew.split_root();
+        assert_eq!(root, Some(0), "returns the top item");
+        assert_eq!(left.size() + right.size(), 19, "subtree sizes are recounted");
+        assert!(left.is_valid() && right.is_valid(), "both subtrees are valid heaps");
+
+        let mut rest = left.debug_multiset();
+        rest.extend(right.debug_multiset());
+        rest.sort_unstable();
+        assert_eq!(rest, (1..20).collect::<Vec<_>>(), "subtrees hold the remaining items");
+    }
+
+    #[test]
+    fn test_try_adopt() {
+        let mut skew = SkewHeap::builder().capacity(5).build().unwrap();
+        skew.put(3);
+
+        let mut other = SkewHeap::new();
+        other.put(1);
+        other.put(2);
+        assert_eq!(skew.try_adopt(other), Ok(3), "returns new size");
+        assert!(skew.drain_equals(&[1, 2, 3]), "adopted items are merged");
+
+        let mut other = SkewHeap::new();
+        other.put(4);
+        other.count = 0;
+        assert_eq!(skew.try_adopt(other), Err(AdoptError::Inconsistent { count: 0, held: 1 }),
+            "rejects a non-empty tree with a size of zero");
+
+        let mut other = SkewHeap::new();
+        other.count = 2;
+        assert_eq!(skew.try_adopt(other), Err(AdoptError::Inconsistent { count: 2, held: 0 }),
+            "rejects an empty tree with a non-zero size");
+
+        let mut other = SkewHeap::new();
+        for n in 0..3 {
+            other.put(n);
+        }
+        assert_eq!(skew.try_adopt(other), Err(AdoptError::AtCapacity), "rejects exceeding capacity");
+
+        let max = SkewHeap::builder().order(Order::Max).build().unwrap();
+        assert_eq!(skew.try_adopt(max), Err(AdoptError::OrderMismatch), "rejects a different order");
+
+        assert_eq!(skew.size(), 3, "rejected heaps leave this heap untouched");
+        assert!(skew.is_valid());
+    }
+
+    #[test]
+    fn test_adopt_with() {
+        let mut skew = SkewHeap::new();
+        for (key, amount) in [(1, 10), (2, 20), (3, 30)] {
+            skew.put(Prioritized::new(key, amount));
+        }
+
+        let mut other = SkewHeap::new();
+        for (key, amount) in [(2, 5), (4, 40), (3, 1)] {
+            other.put(Prioritized::new(key, amount));
+        }
+
+        let resolved = std::cell::Cell::new(0);
+        skew.adopt_with(other, |a, b| {
+            resolved.set(resolved.get() + 1);
+            Prioritized::new(a.priority, a.value + b.value)
+        });
+
+        assert_eq!(resolved.get(), 2, "resolver runs once per collision");
+        assert_eq!(skew.size(), 4, "size reflects the combined unique items");
+
+        let items: Vec<(u32, u32)> = std::iter::from_fn(|| skew.take()).map(|p| p.into_parts()).collect();
+        assert_eq!(items, vec![(1, 10), (2, 25), (3, 31), (4, 40)], "equal keys are combined");
+    }
+
+    #[test]
+    fn test_drop_survives_panicking_item() {
+        use std::cell::Cell;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        #[derive(PartialEq, PartialOrd)]
+        struct Bomb(u32, Rc<Cell<usize>>);
+
+        impl Drop for Bomb {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+
+                if self.0 == 5 {
+                    panic!("bomb 5 exploded");
+                }
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+
+        let mut skew = SkewHeap::new();
+        for n in 0..10 {
+            skew.put(Bomb(n, Rc::clone(&drops)));
+        }
+
+        let result = catch_unwind(AssertUnwindSafe(|| drop(skew)));
+        assert!(result.is_err(), "panic is propagated");
+        assert_eq!(drops.get(), 10, "every item is dropped despite the panic");
+        assert_eq!(Rc::strong_count(&drops), 1, "every node is freed");
+    }
+
+    #[test]
+    fn test_rebalance() {
+        // a valid heap whose every node hangs from the right of the one before
+        let chain = (0..100).flat_map(|n| vec![Some(n), None]).collect();
+        let mut skew = SkewHeap::from_level_order(chain);
+        assert_eq!(skew.right_spine_len(), 100, "heap is degenerate");
+
+        skew.rebalance(100);
+        assert_eq!(skew.right_spine_len(), 100, "heap within the bound is untouched");
+
+        skew.rebalance(10);
+        assert!(skew.right_spine_len() <= 10, "spine is shortened to within the bound");
+        assert_eq!(skew.size(), 100, "size is preserved");
+        assert!(skew.drain_equals(&(0..100).collect::<Vec<_>>()), "contents are preserved");
+    }
+
+    #[test]
+    fn test_trim_to() {
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        skew.trim_to(200);
+        assert_eq!(skew.size(), 100, "trimming to more than the size keeps every item");
+
+        skew.trim_to(10);
+        assert_eq!(skew.size(), 10, "size is reduced to k");
+        assert!(skew.drain_equals(&(0..10).collect::<Vec<_>>()), "the smallest ten remain");
+    }
+
+    #[test]
+    fn test_drain_budget() {
+        let mut skew = SkewHeap::new();
+        for (priority, cost) in [(1, 3), (2, 4), (3, 2), (4, 5), (5, 1)] {
+            skew.put(Prioritized::new(priority, cost));
+        }
+
+        let drained = skew.drain_budget(10, |item| item.value);
+        let drained: Vec<u32> = drained.into_iter().map(|item| item.priority).collect();
+        assert_eq!(drained, vec![1, 2, 3], "takes items in order until the budget would be exceeded");
+        assert_eq!(skew.size(), 2, "size reflects the removed items");
+
+        assert!(skew.drain_budget(4, |item| item.value).is_empty(), "never overshoots the budget");
+        assert_eq!(skew.drain_budget(u64::MAX, |item| item.value).len(), 2, "drains everything");
+    }
+
+    #[test]
+    fn test_first_difference() {
+        let mut a = SkewHeap::new();
+        let mut b = SkewHeap::new();
+        for n in [4, 1, 3, 2, 5] {
+            a.put(n);
+            b.put(if n == 3 { 7 } else { n });
+        }
+
+        assert_eq!(a.first_difference(&a.clone()), None, "identical heaps do not differ");
+        assert_eq!(a.first_difference(&b), Some((2, Some(3), Some(4))), "reports first divergence");
+        assert_eq!(a.size(), 5, "heap is untouched");
+
+        let mut c = a.clone();
+        c.put(6);
+        assert_eq!(a.first_difference(&c), Some((5, None, Some(6))), "reports a heap running out");
+    }
+
+    #[test]
+    fn test_shrink_pool_to() {
+        let mut skew = SkewHeap::builder().recycling().build().unwrap();
+        for n in 0..1000 {
+            skew.put(n);
+        }
+        while skew.take().is_some() {}
+        assert_eq!(skew.pool.len(), 1000, "drained nodes are kept for reuse");
+
+        skew.shrink_pool_to(10);
+        assert_eq!(skew.pool.len(), 10, "spare nodes beyond the cap are freed");
+        assert!(skew.pool.capacity() < 1000, "pool's own storage is shrunk");
+
+        for n in 0..20 {
+            skew.put(n);
+        }
+        assert!(skew.pool.is_empty(), "remaining spare nodes are reused");
+        assert!(skew.drain_equals(&(0..20).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut buf = vec![];
+        SkewHeap::<u32>::new().to_dot(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "digraph SkewHeap {\n}\n", "empty heap");
+
+        // 1 -> (3, 2)
+        let mut skew = SkewHeap::new();
+        for n in 1..=3 {
+            skew.put(n);
+        }
+
+        let mut buf = vec![];
+        skew.to_dot(&mut buf).unwrap();
+        let dot = String::from_utf8(buf).unwrap();
+
+        assert!(dot.starts_with("digraph SkewHeap {\n"), "opens the digraph");
+        assert!(dot.ends_with("}\n"), "closes the digraph");
+
+        for line in [
+            "n0 [label=\"1\"];",
+            "n1 [label=\"3\"];",
+            "n2 [label=\"2\"];",
+            "n0 -> n1 [label=\"L\"];",
+            "n0 -> n2 [label=\"R\"];",
+        ] {
+            assert!(dot.contains(line), "contains {}", line);
+        }
+    }
+
+    #[test]
+    fn test_min_trace() {
+        let mut items: Vec<u32> = (0..100).map(|n| n % 17).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = SkewHeap::new();
+        for n in &items {
+            skew.put(*n);
+        }
+
+        let trace = skew.min_trace();
+        assert!(trace.windows(2).all(|pair| pair[0] <= pair[1]), "trace is non-decreasing");
+
+        items.sort_unstable();
+        assert_eq!(trace, items, "trace holds every item");
+        assert_eq!(skew.size(), 100, "heap is untouched");
+    }
+
+    #[test]
+    fn test_new_with_comparator() {
+        for descending in [false, true] {
+            let cmp: Box<CompareFn<u32>> = if descending {
+                Box::new(|a, b| b.cmp(a))
+            } else {
+                Box::new(|a, b| a.cmp(b))
+            };
+
+            let mut skew = SkewHeap::new_with_comparator(cmp);
+            for n in [3, 1, 4, 1, 5, 9, 2, 6] {
+                skew.put(n);
+            }
+
+            let expected = if descending { [9, 6, 5, 4, 3, 2, 1, 1] } else { [1, 1, 2, 3, 4, 5, 6, 9] };
+            assert!(skew.clone().drain_equals(&expected), "clone shares the comparator");
+            assert_eq!(skew.min_trace(), expected, "drains in the comparator's order");
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.fold(0, |sum, n| sum + n), 0, "folding an empty heap yields init");
+
+        for n in 1..=100 {
+            skew.put(n);
+        }
+
+        assert_eq!(skew.fold(0, |sum, n| sum + n), 5050, "folds over every item");
+        assert_eq!(skew.fold(0, |count, _| count + 1), 100, "visits each item once");
+        assert_eq!(skew.size(), 100, "heap is untouched");
+    }
+
+    #[test]
+    fn test_deepest_path() {
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.height(), 0, "empty heap has no height");
+        assert!(skew.deepest_path().is_empty(), "empty heap has no path");
+
+        // 1 -> (3 -> (5), 2 -> (4))
+        for n in 1..=5 {
+            skew.put(n);
+        }
+
+        assert_eq!(skew.height(), 3, "height counts the nodes along the longest path");
+
+        let path = skew.deepest_path();
+        assert_eq!(path.len(), skew.height(), "path is as long as the heap is high");
+        assert_eq!(path.first(), skew.peek(), "path starts at the root");
+        assert_eq!(path, vec![1, 3, 5], "leftmost of the deepest paths is chosen");
+    }
+
+    #[test]
+    fn test_drain_sum() {
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.drain_sum(), (vec![], 0), "empty heap sums to zero");
+
+        for n in [5, 3, 8, 1, 3] {
+            skew.put(n);
+        }
+
+        assert_eq!(skew.drain_sum(), (vec![1, 3, 3, 5, 8], 20), "returns drained items and their sum");
+        assert!(skew.is_empty(), "heap is drained");
+    }
+
+    #[test]
+    fn test_take_from_structure() {
+        // 0 -> (100, 1 -> (., 2 -> (., ...)))
+        let spine = (1..10).rev().fold(Shape::Empty, |tail, n| Shape::node(n, Shape::Empty, tail));
+        let mut skew = SkewHeap::from_structure(Shape::node(0, Shape::leaf(100), spine));
+        assert_eq!(skew.size(), 11, "size counts every node");
+        assert_eq!(skew.right_spine_len(), 10, "builds the exact shape");
+
+        assert_eq!(skew.take(), Some(0), "take on a deep right spine");
+        assert_eq!(skew.right_spine_len(), 1, "merging along the spine swaps it to the left");
+        assert!(skew.is_valid());
+        assert!(skew.drain_equals(&(1..10).chain(Some(100)).collect::<Vec<_>>()));
+
+        let balanced = Shape::node(1,
+            Shape::node(2, Shape::leaf(4), Shape::leaf(5)),
+            Shape::node(3, Shape::leaf(6), Shape::leaf(7)),
+        );
+
+        let mut skew = SkewHeap::from_structure(balanced);
+        assert_eq!(skew.to_level_order(), (1..=7).map(Some).collect::<Vec<_>>(), "builds the exact shape");
+        assert_eq!(skew.take(), Some(1), "take on a balanced tree");
+        // 2 -> (3 -> (5 -> (7), 6), 4)
+        assert_eq!(skew.to_level_order(), vec![Some(2), Some(3), Some(4), Some(5), Some(6), None, None, Some(7)]);
+        assert!(skew.drain_equals(&[2, 3, 4, 5, 6, 7]));
+    }
+
+    #[test]
+    fn test_shape_metrics() {
+        let empty = ShapeMetrics { size: 0, height: 0, right_spine_len: 0, internal_path_length: 0 };
+        assert_eq!(SkewHeap::<u32>::new().shape_metrics(), empty, "empty heap");
+
+        let skew = SkewHeap::from_structure(Shape::node(1,
+            Shape::node(2, Shape::leaf(4), Shape::Empty),
+            Shape::node(3, Shape::Empty, Shape::node(5, Shape::leaf(6), Shape::Empty)),
+        ));
+
+        let metrics = skew.shape_metrics();
+        assert_eq!(metrics, ShapeMetrics {
+            size:                 6,
+            height:               4,
+            right_spine_len:      3,
+            internal_path_length: 9,
+        });
+
+        assert_eq!(metrics.height, skew.height(), "agrees with height");
+        assert_eq!(metrics.right_spine_len, skew.right_spine_len(), "agrees with right_spine_len");
+    }
+
+    #[test]
+    fn test_try_peek() {
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.try_peek(), Err(EmptyHeap), "errors when empty");
+
+        skew.put(2);
+        skew.put(1);
+        assert_eq!(skew.try_peek(), Ok(1), "returns the top item");
+        assert_eq!(skew.size(), 2, "try_peek does not remove");
+
+        let next = || -> Result<u32, EmptyHeap> { Ok(skew.try_peek()? * 10) };
+        assert_eq!(next(), Ok(10), "composes with ?");
+    }
+
+    #[test]
+    fn test_adopt_all() {
+        let mut skew = SkewHeap::new();
+        skew.put(0);
+
+        let others: Vec<SkewHeap<u32>> = (0..4).map(|i| {
+            let mut other = SkewHeap::new();
+            for n in 0..5 {
+                other.put(1 + n * 4 + i);
+            }
+            other
+        }).collect();
+
+        assert_eq!(skew.adopt_all(others), 21, "returns the combined size");
+        assert!(skew.is_valid());
+        assert!(skew.drain_equals(&(0..21).collect::<Vec<_>>()), "holds every heap's items");
+
+        assert_eq!(skew.adopt_all(vec![SkewHeap::new()]), 21, "adopting only empty heaps");
+    }
+
+    /// Builds a heap from items, drains it, and returns the number of comparisons made between
+    /// items along the way
+    fn count_comparisons_for_drain<T: PartialOrd>(items: Vec<T>) -> usize {
+        use std::cell::Cell;
+        use std::cmp::Ordering;
+
+        struct Counted<'a, T> {
+            item:        T,
+            comparisons: &'a Cell<usize>,
+        }
+
+        impl<T: PartialOrd> PartialEq for Counted<'_, T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.item == other.item
+            }
+        }
+
+        impl<T: PartialOrd> PartialOrd for Counted<'_, T> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.comparisons.set(self.comparisons.get() + 1);
+                self.item.partial_cmp(&other.item)
+            }
+        }
+
+        let comparisons = Cell::new(0);
+
+        let mut skew = SkewHeap::new();
+        for item in items {
+            skew.put(Counted { item, comparisons: &comparisons });
+        }
+
+        while skew.take().is_some() {}
+        comparisons.get()
+    }
+
+    #[test]
+    fn test_count_comparisons_for_drain() {
+        let n_log_n = |n: usize| n * (usize::BITS - n.leading_zeros()) as usize;
+
+        for n in [1 << 10, 1 << 12, 1 << 14] {
+            let comparisons = count_comparisons_for_drain((0..n).collect());
+            let ratio = comparisons as f64 / n_log_n(n) as f64;
+            assert!((0.5..4.0).contains(&ratio), "{} comparisons for {} sorted items", comparisons, n);
+        }
+    }
+
+    #[test]
+    fn test_skewheap_macro() {
+        let skew = skewheap![3, 1, 2];
+        assert_eq!(skew.order(), Order::Min, "builds a min heap by default");
+        assert!(skew.drain_equals(&[1, 2, 3]));
+
+        let skew = skewheap![max; 3, 1, 2,];
+        assert_eq!(skew.order(), Order::Max, "builds a max heap");
+        assert!(skew.drain_equals(&[3, 2, 1]));
+
+        let skew: SkewHeap<u32> = skewheap![];
+        assert!(skew.is_empty(), "builds an empty heap");
+    }
+
+    #[test]
+    fn test_has_incomparable() {
+        let mut skew = SkewHeap::new();
+        assert!(!skew.has_incomparable(), "empty heap");
+
+        for n in [1.5, -2.0, 3.25] {
+            skew.put(n);
+        }
+        assert!(!skew.has_incomparable(), "ordinary floats are comparable");
+
+        skew.put(f64::NAN);
+        skew.put(0.0);
+        assert!(skew.has_incomparable(), "detects a NaN");
+    }
+
+    #[test]
+    fn test_as_item_slice_unsorted() {
+        let skew = skewheap![4, 2, 7, 2, 9, 1];
+
+        let mut items: Vec<u32> = skew.as_item_slice_unsorted().into_iter().copied().collect();
+        items.sort_unstable();
+        assert_eq!(items, vec![1, 2, 2, 4, 7, 9], "references every item");
+
+        assert_eq!(skew.as_item_slice_unsorted()[0], &1, "traversal starts at the root");
+        assert!(SkewHeap::<u32>::new().as_item_slice_unsorted().is_empty(), "empty heap");
+    }
+
+    #[test]
+    fn test_extract_below() {
+        let mut skew = SkewHeap::new();
+        for n in 0..10 {
+            skew.put(n);
+        }
+
+        let below = skew.extract_below(&5);
+        assert_eq!(below.size(), 5, "extracted heap counts its items");
+        assert_eq!(skew.size(), 5, "remaining heap counts its items");
+        assert!(below.drain_equals(&[0, 1, 2, 3, 4]), "items below the threshold are extracted");
+        assert!(skew.drain_equals(&[5, 6, 7, 8, 9]), "the rest remain");
+
+        let mut max = skewheap![max; 1, 8, 3, 6];
+        assert!(max.extract_below(&4).drain_equals(&[3, 1]), "extracted heap keeps the order");
+        assert!(max.drain_equals(&[8, 6]));
+    }
+
+    #[test]
+    fn test_sorted_snapshot() {
+        let skew = skewheap![5, 3, 9, 1, 3];
+        let shape = skew.to_level_order();
+
+        assert_eq!(skew.sorted_snapshot(), vec![1, 3, 3, 5, 9], "items in the order they would be taken");
+        assert_eq!(skew.size(), 5, "size is unchanged");
+        assert_eq!(skew.to_level_order(), shape, "tree is unchanged");
+        assert_eq!(SkewHeap::heapify(vec![9, 3, 5, 3, 1]).sorted_snapshot(), skew.sorted_snapshot(),
+            "independent of the shape of the tree");
+    }
+
+    #[test]
+    fn test_merge_iter() {
+        let mut items: Vec<u32> = (0..1000).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = skewheap![500, 2000];
+        skew.merge_iter(items);
+        assert_eq!(skew.size(), 1002, "size counts every inserted item");
+        assert!(skew.is_valid());
+
+        let mut expected: Vec<u32> = (0..1000).chain([500, 2000].iter().copied()).collect();
+        expected.sort_unstable();
+        assert!(skew.drain_equals(&expected), "holds both the batch and the original items");
+
+        let mut extended = skewheap![3];
+        extended.extend(vec![2, 1]);
+        assert!(extended.drain_equals(&[1, 2, 3]), "extend puts each item");
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_assert_no_aliasing() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
 
-        let mut b = SkewHeap::new();
-        b.put(4);
-        b.put(5);
-        b.put(6);
+        let mut skew = skewheap![5, 3, 8];
+        let mut other = SkewHeap::new();
+        other.merge_iter(vec![4, 1, 9, 7]);
+        skew.adopt(other);
+        skew.take();
+        skew.assert_no_aliasing();
 
-        a.adopt(b);
-        assert_eq!(a.size(), 6);
-        assert_eq!(a.take(), Some(1));
-        assert_eq!(a.take(), Some(2));
-        assert_eq!(a.take(), Some(3));
-        assert_eq!(a.take(), Some(4));
-        assert_eq!(a.take(), Some(5));
-        assert_eq!(a.take(), Some(6));
+        // 1 -> (2, 3), with 3 made to point back at the root
+        let skew = SkewHeap::from_structure(Shape::node(1, Shape::leaf(2), Shape::leaf(3)));
+        unsafe{ (*(*skew.root).right).left = skew.root }
+
+        let result = catch_unwind(AssertUnwindSafe(|| skew.assert_no_aliasing()));
+        assert!(result.is_err(), "panics on a cycle");
+
+        // break the cycle so that the heap may be dropped
+        unsafe{ (*(*skew.root).right).left = std::ptr::null_mut() }
+        skew.assert_no_aliasing();
     }
 
     #[test]
-    fn test_large_randomized_input() {
-        let size = 1000;
+    fn test_max_tracking() {
+        let mut skew = SkewHeap::with_max_tracking();
+        assert_eq!(skew.peek_max(), None, "peek_max returns None when empty");
 
-        let mut items: Vec<u32> = (0..size).collect();
-        items.shuffle(&mut thread_rng());
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
 
-        let mut skew = SkewHeap::new();
+        let mut max = 0;
         for n in items {
             skew.put(n);
+            max = max.max(n);
+            assert_eq!(skew.peek_max(), Some(&max), "tracks max across puts");
         }
 
-        for n in 0..size {
+        for n in 0..50 {
             assert_eq!(skew.take(), Some(n));
+            assert_eq!(skew.peek_max(), Some(&99), "taking other items leaves max in place");
+        }
+
+        let mut other = SkewHeap::new();
+        other.put(150);
+        other.put(120);
+        skew.adopt(other);
+        assert_eq!(skew.peek_max(), Some(&150), "tracks max across adopt");
+
+        let mut clone = skew.clone();
+        assert_eq!(clone.peek_max(), Some(&150), "clone tracks its own max");
+        clone.reset_for_reuse();
+        assert_eq!(clone.peek_max(), None, "reset clears max");
+
+        skew.for_each_mut(|n| *n = 200 - *n);
+        assert_eq!(skew.peek_max(), Some(&150), "tracks max across for_each_mut");
+        skew.for_each_mut(|n| *n = 200 - *n);
+
+        let mut max_heap = SkewHeap::with_max_tracking();
+        max_heap.put(7);
+        max_heap.put(7);
+        max_heap.put(3);
+        assert_eq!(max_heap.take(), Some(3));
+        assert_eq!(max_heap.take(), Some(7), "taking the max itself");
+        assert_eq!(max_heap.peek_max(), Some(&7), "max is recomputed once taken");
+        assert_eq!(max_heap.take(), Some(7));
+        assert_eq!(max_heap.peek_max(), None, "max is cleared once drained");
+
+        let untracked = skewheap![4, 9, 2];
+        assert_eq!(untracked.peek_max(), Some(&9), "peek_max without tracking");
+        assert!(skew.drain_equals(&(50..100).chain([120, 150].iter().copied()).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn test_drain_to_sender() {
+        use std::sync::mpsc::channel;
+
+        let mut skew = skewheap![4, 1, 3, 2];
+        let (tx, rx) = channel();
+
+        assert_eq!(skew.drain_to_sender(&tx), Ok(4), "returns the number of items sent");
+        assert!(skew.is_empty(), "heap is drained");
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4], "items are received in order");
+
+        let mut skew = skewheap![6, 5, 7];
+        drop(rx);
+        assert_eq!(skew.drain_to_sender(&tx).map_err(|err| err.0), Err(5), "error holds the unsent item");
+        assert!(skew.drain_equals(&[6, 7]), "the remainder stays in the heap");
+    }
+
+    #[test]
+    fn test_pop_expired() {
+        let mut timers = skewheap![30, 10, 50, 20, 40, 20];
+
+        assert!(timers.pop_expired(5).is_empty(), "nothing has expired yet");
+        assert_eq!(timers.pop_expired(20), vec![10, 20, 20], "deadlines up to now have expired");
+        assert_eq!(timers.size(), 3, "size reflects the removals");
+        assert_eq!(timers.pop_expired(45), vec![30, 40]);
+        assert!(timers.drain_equals(&[50]), "later deadlines remain");
+    }
+
+    #[test]
+    fn test_clone_smallest() {
+        let mut items: Vec<u32> = (0..50).collect();
+        items.shuffle(&mut rng());
+        let skew = SkewHeap::heapify(items);
+
+        let preview = skew.clone_smallest(5);
+        assert!(preview.drain_equals(&[0, 1, 2, 3, 4]), "holds exactly the k smallest");
+        assert_eq!(skew.size(), 50, "source is unchanged");
+        assert_eq!(skew.clone_smallest(100).size(), 50, "holds every item when fewer than k");
+    }
+
+    #[test]
+    fn test_peek_is_idempotent() {
+        let mut items: Vec<u32> = (0..100).chain(0..10).collect();
+        items.shuffle(&mut rng());
+
+        let skew = SkewHeap::heapify(items);
+        let (count, root, before) = (skew.size(), skew.root, skew.sorted_snapshot());
+
+        for _ in 0..100 {
+            assert_eq!(skew.peek(), Some(&0), "peek returns the top item");
+        }
+
+        assert_eq!(skew.size(), count, "peek does not change the count");
+        assert_eq!(skew.root, root, "peek does not change the root");
+        assert_eq!(skew.sorted_snapshot(), before, "peek does not change the contents");
+        assert!(skew.is_valid());
+    }
+
+    #[test]
+    fn test_put_coalescing() {
+        let add = |top: &mut Prioritized<u32, u32>, item: Prioritized<u32, u32>| top.value += item.value;
+
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.put_coalescing(Prioritized::new(2, 10), add), 1, "inserts into an empty heap");
+        assert_eq!(skew.put_coalescing(Prioritized::new(3, 5), add), 2, "inserts an item not at the top");
+        assert_eq!(skew.put_coalescing(Prioritized::new(2, 7), add), 2, "coalesces with the top");
+        assert_eq!(skew.put_coalescing(Prioritized::new(3, 1), add), 3, "inserts an item equal to a non-top item");
+        assert_eq!(skew.peek().map(|top| top.value), Some(17), "payloads are combined");
+
+        // a combiner which pushes the top item down the order
+        skew.put_coalescing(Prioritized::new(2, 0), |top, _| top.priority = 4);
+        assert!(skew.is_valid(), "reordered top item is moved");
+        assert_eq!(skew.size(), 3);
+
+        let drained: Vec<(u32, u32)> = std::iter::from_fn(|| skew.take()).map(Prioritized::into_parts).collect();
+        assert_eq!(drained[2], (4, 17), "reordered item is taken in its new position");
+    }
+
+    #[test]
+    fn test_distinct_count() {
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert_eq!(empty.distinct_count(), 0, "empty heap has no distinct items");
+
+        let skew = SkewHeap::heapify(vec![3, 1, 2, 3, 1, 3, 5]);
+        assert_eq!(skew.distinct_count(), 4, "repeats are counted once");
+        assert!(skew.distinct_count() < skew.size(), "size counts repeats");
+        assert_eq!(skew.size(), 7, "heap is not disturbed");
+        assert_eq!(skew.peek(), Some(&1), "heap is not disturbed");
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut a = SkewHeap::heapify(vec![3, 1, 2]);
+        let mut b = SkewHeap::heapify(vec![20, 10]);
+
+        a.swap(&mut b);
+        assert_eq!((a.size(), b.size()), (2, 3), "counts are exchanged");
+        assert!(a.is_valid() && b.is_valid());
+        assert_eq!(a.sorted_snapshot(), vec![10, 20], "contents are exchanged");
+        assert_eq!(b.sorted_snapshot(), vec![1, 2, 3], "contents are exchanged");
+
+        let mut empty = SkewHeap::new();
+        a.swap(&mut empty);
+        assert!(a.is_empty(), "swapped with an empty heap");
+        assert_eq!(empty.take(), Some(10), "empty heap receives the items");
+    }
+
+    #[test]
+    fn test_node_is_valid() {
+        let skew = SkewHeap::from_structure(Shape::node(1,
+            Shape::node(2, Shape::leaf(5), Shape::leaf(3)),
+            Shape::node(4, Shape::Empty, Shape::leaf(6)),
+        ));
+
+        assert!(skew.node_is_valid(std::ptr::null_mut()), "null subtree is valid");
+        assert!(skew.node_is_valid(skew.root), "whole tree is valid");
+
+        let (left, right) = unsafe{ ((*skew.root).left, (*skew.root).right) };
+        assert!(skew.node_is_valid(left), "left subtree is valid");
+
+        // merge the root's subtrees in isolation, reattaching the result so that it is freed
+        let merged = skew.merge(left, right);
+        unsafe{
+            (*skew.root).left = merged;
+            (*skew.root).right = std::ptr::null_mut();
+        }
+
+        assert!(skew.node_is_valid(merged), "merging valid subtrees gives a valid subtree");
+        assert!(skew.is_valid(), "heap remains valid");
+    }
+
+    #[test]
+    fn test_node_is_valid_inverted() {
+        let skew = SkewHeap::from_structure(Shape::node(1,
+            Shape::node(2, Shape::leaf(3), Shape::Empty),
+            Shape::node(6, Shape::leaf(4), Shape::leaf(7)),
+        ));
+
+        let (left, right) = unsafe{ ((*skew.root).left, (*skew.root).right) };
+        assert!(skew.node_is_valid(left), "ordered subtree is valid");
+        assert!(!skew.node_is_valid(right), "inverted subtree is rejected");
+        assert!(!skew.node_is_valid(skew.root), "tree containing an inverted subtree is rejected");
+    }
+
+    #[test]
+    fn test_from_unsorted() {
+        let mut items: Vec<u32> = (0..1000).collect();
+        items.shuffle(&mut rng());
+
+        let skew = SkewHeap::from_unsorted(items.clone());
+        assert_eq!(skew.size(), 1000, "counts every item");
+        assert!(skew.is_valid());
+
+        items.sort_unstable();
+        assert_eq!(skew.sorted_snapshot(), items, "holds every item");
+    }
+
+    #[test]
+    fn test_into_sorted_peekable() {
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut sorted = SkewHeap::heapify(items).into_sorted_peekable();
+        assert_eq!(sorted.size_hint(), (100, Some(100)));
+
+        for n in 0..100 {
+            assert_eq!(sorted.peek(), Some(&n), "peek returns the next item");
+            assert_eq!(sorted.peek(), Some(&n), "peek does not advance");
+            assert_eq!(sorted.next(), Some(n), "next returns the peeked item");
+        }
+
+        assert_eq!(sorted.peek(), None, "peek returns None when exhausted");
+        assert_eq!(sorted.next(), None, "next returns None when exhausted");
+    }
+
+    #[test]
+    fn test_adopt_converted() {
+        let mut wide: SkewHeap<u64> = SkewHeap::heapify(vec![1 << 40, 4, 0]);
+        let narrow: SkewHeap<u32> = SkewHeap::heapify(vec![3, 1, 5]);
+
+        wide.adopt_converted(narrow, Into::into);
+        assert_eq!(wide.size(), 6, "counts the adopted items");
+        assert!(wide.is_valid());
+        assert_eq!(wide.sorted_snapshot(), vec![0, 1, 3, 4, 5, 1 << 40], "drains the combined items in order");
+    }
+
+    #[test]
+    fn test_median() {
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert_eq!(empty.median(), None, "empty heap has no median");
+
+        let mut items: Vec<u32> = (0..101).collect();
+        items.shuffle(&mut rng());
+
+        let odd = SkewHeap::heapify(items.clone());
+        assert_eq!(odd.median(), Some(50), "middle item of an odd count");
+        assert_eq!(odd.size(), 101, "heap is not modified");
+
+        let even = SkewHeap::heapify(items.into_iter().filter(|&n| n != 100).collect());
+        assert_eq!(even.median(), Some(49), "lower middle item of an even count");
+
+        let single = SkewHeap::heapify(vec![7]);
+        assert_eq!(single.median(), Some(7), "lone item is the median");
+    }
+
+    #[test]
+    fn test_items_in_range() {
+        let mut items: Vec<u32> = (0..20).collect();
+        items.shuffle(&mut rng());
+
+        let skew = SkewHeap::heapify(items);
+        assert_eq!(skew.items_in_range(&5, &10), vec![5, 6, 7, 8, 9, 10], "bounds are inclusive");
+        assert_eq!(skew.items_in_range(&25, &30), Vec::<u32>::new(), "no items in range");
+        assert_eq!(skew.size(), 20, "heap is not modified");
+    }
+
+    #[test]
+    fn test_into_sorted_stream() {
+        use std::cell::Cell;
+
+        // counts the comparisons made, which only extraction requires
+        #[derive(Debug)]
+        struct Counted<'a>(u32, &'a Cell<usize>);
+
+        impl PartialEq for Counted<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl PartialOrd for Counted<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.1.set(self.1.get() + 1);
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let comparisons = Cell::new(0);
+
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let skew = SkewHeap::heapify(items.into_iter().map(|n| Counted(n, &comparisons)).collect());
+        let mut stream = skew.into_sorted_stream();
+
+        let firsts: Vec<u32> = stream.by_ref().take(3).map(|item| item.0).collect();
+        assert_eq!(firsts, vec![0, 1, 2], "yields the smallest items in order");
+
+        let compared = comparisons.get();
+        drop(stream);
+
+        assert_eq!(comparisons.get(), compared, "remaining items are freed without extraction");
+    }
+
+    #[test]
+    fn test_intersects() {
+        let a = SkewHeap::heapify(vec![1, 3, 5, 7, 9]);
+        let b = SkewHeap::heapify(vec![2, 4, 6, 7]);
+        let c = SkewHeap::heapify(vec![0, 2, 4]);
+        let empty = SkewHeap::new();
+
+        assert!(a.intersects(&b), "heaps sharing an item intersect");
+        assert!(b.intersects(&a), "intersects is symmetric");
+        assert!(!a.intersects(&c), "disjoint heaps do not intersect");
+        assert!(!a.intersects(&empty), "no heap intersects an empty heap");
+        assert_eq!((a.size(), b.size()), (5, 4), "heaps are not modified");
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = SkewHeap::heapify(vec![1, 2, 2, 3]);
+        let b = SkewHeap::heapify(vec![2, 2, 4]);
+
+        let common = a.intersection(&b);
+        assert_eq!(common.size(), 2, "counts the common items");
+        assert_eq!(common.sorted_snapshot(), vec![2, 2], "keeps the lesser multiplicity");
+        assert_eq!(b.intersection(&a).sorted_snapshot(), vec![2, 2], "intersection is symmetric");
+
+        let single = SkewHeap::heapify(vec![2, 3, 3]);
+        assert_eq!(a.intersection(&single).sorted_snapshot(), vec![2, 3], "keeps the lesser multiplicity");
+        assert!(a.intersection(&SkewHeap::new()).is_empty(), "nothing in common with an empty heap");
+        assert_eq!((a.size(), b.size()), (4, 3), "heaps are not modified");
+    }
+
+    #[test]
+    fn test_merge_strategy() {
+        let mut items: Vec<u32> = (0..1000).collect();
+        items.shuffle(&mut rng());
+
+        let build = |strategy| {
+            let mut skew = SkewHeap::builder().merge_strategy(strategy).build().unwrap();
+            for n in &items {
+                skew.put(*n);
+            }
+            skew
+        };
+
+        // a preorder walk noting which children each node has identifies the tree exactly
+        let shape = |skew: &SkewHeap<u32>| -> Vec<(u32, bool, bool)> {
+            Nodes::new(skew.root)
+                .map(|(node, _)| (node.item, !node.left.is_null(), !node.right.is_null()))
+                .collect()
+        };
+
+        let mut recursive = build(MergeStrategy::Recursive);
+        let mut iterative = build(MergeStrategy::Iterative);
+        assert!(iterative.is_valid());
+        assert_eq!(shape(&iterative), shape(&recursive), "strategies build identical trees");
+
+        while let Some(n) = recursive.take() {
+            assert_eq!(iterative.take(), Some(n), "strategies take items in the same order");
+            assert_eq!(shape(&iterative), shape(&recursive), "trees stay identical");
+        }
+
+        assert!(iterative.is_empty());
+    }
+
+    #[test]
+    fn test_take_matching() {
+        let mut skew = SkewHeap::heapify(vec![7, 3, 9, 5, 8, 1, 4]);
+
+        assert_eq!(skew.take_matching(|n| n % 2 == 0), Some(4), "takes the smallest matching item");
+        assert_eq!(skew.size(), 6, "only the match is removed");
+        assert!(skew.is_valid(), "skipped items are merged back");
+
+        assert_eq!(skew.take_matching(|&n| n > 100), None, "None when nothing matches");
+        assert_eq!(skew.size(), 6, "nothing is removed when nothing matches");
+        assert_eq!(skew.sorted_snapshot(), vec![1, 3, 5, 7, 8, 9], "skipped items remain");
+
+        let mut tracked = SkewHeap::with_max_tracking();
+        tracked.put(1);
+        tracked.put(2);
+        assert_eq!(tracked.take_matching(|&n| n == 2), Some(2), "takes the max");
+        assert_eq!(tracked.peek_max(), Some(&1), "max accounts for skipped items");
+    }
+
+    #[test]
+    fn test_value_distribution() {
+        let empty: SkewHeap<u32> = SkewHeap::new();
+        assert!(empty.value_distribution().is_empty(), "empty heap has no values");
+
+        let skew = SkewHeap::heapify(vec![4, 1, 4, 2, 4, 1, 9]);
+        assert_eq!(skew.value_distribution(), vec![(1, 2), (2, 1), (4, 3), (9, 1)], "counts each value");
+        assert_eq!(skew.size(), 7, "heap is not modified");
+    }
+
+    #[test]
+    fn test_sum_of_smallest() {
+        let mut items: Vec<u32> = (1..=100).collect();
+        items.shuffle(&mut rng());
+
+        let skew = SkewHeap::heapify(items);
+        assert_eq!(skew.sum_of_smallest(3), 1 + 2 + 3, "sums the k smallest items");
+        assert_eq!(skew.sum_of_smallest(0), 0, "sum of no items is zero");
+        assert_eq!(skew.sum_of_smallest(1000), 5050, "sums every item when there are fewer than k");
+        assert_eq!(skew.size(), 100, "heap is not modified");
+    }
+
+    #[test]
+    fn test_as_reversed() {
+        let mut items: Vec<u32> = (0..100).collect();
+        items.shuffle(&mut rng());
+
+        let mut reversed = SkewHeap::heapify(items).as_reversed();
+        assert_eq!(reversed.size(), 100, "keeps every item");
+        assert!(reversed.is_valid());
+
+        let drained: Vec<u32> = std::iter::from_fn(|| reversed.take()).map(|item| item.0).collect();
+        assert_eq!(drained, (0..100).rev().collect::<Vec<_>>(), "takes items in descending order");
+    }
+
+    #[test]
+    fn test_drain_with_seq() {
+        let mut skew = SkewHeap::builder().sequenced().build().unwrap();
+
+        for item in [Prioritized::new(3, 'a'), Prioritized::new(1, 'b'), Prioritized::new(3, 'c'),
+                     Prioritized::new(2, 'd'), Prioritized::new(1, 'e')] {
+            skew.put(item);
+        }
+
+        let drained: Vec<(u32, char, u64)> = skew.drain_with_seq().into_iter()
+            .map(|(item, seq)| (item.priority, item.value, seq))
+            .collect();
+
+        assert_eq!(drained, vec![(1, 'b', 1), (1, 'e', 4), (2, 'd', 3), (3, 'a', 0), (3, 'c', 2)],
+            "each item is paired with its insertion index");
+        assert!(skew.is_empty(), "every item is drained");
+    }
+
+    /// Performs a seeded random interleaving of put and take, checking each take against a shadow
+    /// model: the items held, and the last item taken. Unless an item smaller than the last one
+    /// taken has since been put, the next take must not be smaller than it.
+    fn check_monotonic_takes(seed: u64, ops: usize) {
+        use rand::{RngExt, SeedableRng};
+        use rand::rngs::StdRng;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut skew = SkewHeap::new();
+        let mut shadow: Vec<u32> = vec![];
+        let mut last: Option<u32> = None;
+
+        for _ in 0..ops {
+            if rng.random_range(0..3) < 2 {
+                let item = rng.random_range(0..1000);
+
+                // a smaller item resets the floor set by the last take
+                if last.is_some_and(|last| item < last) {
+                    last = None;
+                }
+
+                skew.put(item);
+                shadow.push(item);
+            } else {
+                let expected = shadow.iter().copied().min();
+                let item = skew.take();
+                assert_eq!(item, expected, "take returns the smallest item held (seed {})", seed);
+
+                if let Some(item) = item {
+                    assert!(last.is_none_or(|last| item >= last), "take output decreased (seed {})", seed);
+                    shadow.remove(shadow.iter().position(|&n| n == item).unwrap());
+                    last = Some(item);
+                }
+            }
+
+            assert_eq!(skew.size(), shadow.len(), "size matches the shadow model (seed {})", seed);
+        }
+
+        assert!(skew.is_valid());
+    }
+
+    #[test]
+    fn test_monotonic_takes() {
+        for seed in [1, 2, 3, 42] {
+            check_monotonic_takes(seed, 5000);
+        }
+    }
+
+    #[test]
+    fn test_recycling() {
+        let mut skew = SkewHeap::builder().recycling().build().unwrap();
+        skew.put(2);
+        skew.put(1);
+
+        assert_eq!(skew.take(), Some(1));
+        assert_eq!(skew.pool.len(), 1, "take returns its node to the pool when recycling");
+
+        skew.put(3);
+        assert!(skew.pool.is_empty(), "put reuses the recycled node");
+
+        let mut plain = SkewHeap::new();
+        plain.put(1);
+        plain.take();
+        assert!(plain.pool.is_empty(), "take frees its node by default");
+    }
+
+    #[test]
+    fn test_adopt_interned_into_plain() {
+        let interned = || {
+            let mut heap = SkewHeap::builder().interning().build().unwrap();
+            for _ in 0..5 {
+                heap.put(7);
+            }
+            heap
+        };
+
+        let mut skew = SkewHeap::new();
+        skew.adopt(interned());
+        assert_eq!(skew.size(), 5, "counts every adopted item");
+        assert!(skew.is_valid());
+        assert_eq!(std::iter::from_fn(|| skew.take()).count(), 5, "every adopted item can be taken");
+        assert!(skew.is_empty());
+
+        let mut skew = SkewHeap::new();
+        assert_eq!(skew.try_adopt(interned()), Ok(5));
+        assert_eq!(skew.adopt_all(vec![interned(), interned()]), 15);
+        assert_eq!(std::iter::from_fn(|| skew.take()).count(), 15, "every adopted item can be taken");
+
+        let mut skew = SkewHeap::new().merged(interned());
+        assert_eq!(std::iter::from_fn(|| skew.take()).count(), 5, "every merged item can be taken");
+    }
+
+    #[test]
+    fn test_bounded_union_bounded_first() {
+        let mut first = SkewHeap::builder().capacity(2).build().unwrap();
+        first.put(5);
+        first.put(1);
+
+        let union = SkewHeap::bounded_union(vec![first, SkewHeap::heapify(vec![3, 0])], 2);
+        assert_eq!(union.capacity(), Some(2), "takes the first heap's configuration");
+        assert_eq!(union.sorted_snapshot(), vec![0, 1], "keeps the cap items taken first");
+
+        let mut first = SkewHeap::builder().capacity(2).build().unwrap();
+        first.put(5);
+
+        let union = SkewHeap::bounded_union(vec![first, SkewHeap::heapify(vec![3, 0, 4])], 10);
+        assert_eq!(union.sorted_snapshot(), vec![0, 3], "never exceeds the first heap's capacity");
+    }
+
+    #[test]
+    fn test_adopt_with_bounded() {
+        let mut skew = SkewHeap::builder().capacity(3).build().unwrap();
+        for (priority, value) in [(1, 10), (2, 20), (3, 30)] {
+            skew.put(Prioritized::new(priority, value));
+        }
+
+        let other = SkewHeap::heapify(vec![Prioritized::new(1, 1), Prioritized::new(3, 3)]);
+        skew.adopt_with(other, |a, b| Prioritized::new(a.priority, a.value + b.value));
+
+        assert_eq!(skew.capacity(), Some(3), "configuration is kept");
+        assert_eq!(skew.size(), 3, "combined items fit within capacity");
+
+        let drained: Vec<(u32, u32)> = std::iter::from_fn(|| skew.take()).map(Prioritized::into_parts).collect();
+        assert_eq!(drained, vec![(1, 11), (2, 20), (3, 33)], "equal items are combined");
+    }
+
+    #[test]
+    fn test_adopt_untracked_skips_find_max() {
+        use std::cell::Cell;
+
+        #[derive(Debug)]
+        struct Counted<'a>(u32, &'a Cell<usize>);
+
+        impl PartialEq for Counted<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl PartialOrd for Counted<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.1.set(self.1.get() + 1);
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let comparisons = Cell::new(0);
+        let mut items: Vec<u32> = (0..1024).collect();
+        items.shuffle(&mut rng());
+
+        let make = || SkewHeap::heapify(items.iter().map(|&n| Counted(n, &comparisons)).collect());
+
+        let mut skew = SkewHeap::new();
+        skew.put(Counted(500, &comparisons));
+        let other = make();
+        comparisons.set(0);
+        skew.adopt(other);
+
+        // searching the adopted tree's leaves for its max would take hundreds of comparisons
+        assert!(comparisons.get() < 64, "adopt made {} comparisons", comparisons.get());
+
+        let mut skew = SkewHeap::new();
+        let others = vec![make(), make()];
+        comparisons.set(0);
+        skew.adopt_all(others);
+        assert!(comparisons.get() < 64, "adopt_all made {} comparisons", comparisons.get());
+
+        let mut tracked = SkewHeap::with_max_tracking();
+        tracked.adopt(make());
+        assert_eq!(tracked.peek_max().map(|item| item.0), Some(1023), "tracked max is still updated");
+    }
+
+    #[test]
+    fn test_merge_strategy_long_spine() {
+        // the root's children are right spines far too long to recurse down together on a test
+        // thread's stack: one of odd items and one of even items, which interleave when merged
+        let depth: u32 = 500_000;
+        let spine = |first: u32| {
+            let mut shape = Shape::Empty;
+            for n in (0..depth).rev() {
+                shape = Shape::node(first + 2 * n, Shape::Empty, shape);
+            }
+            shape
+        };
+
+        let build = || {
+            let mut skew = SkewHeap::from_structure(Shape::node(0, spine(1), spine(2)));
+            skew.strategy = MergeStrategy::Iterative;
+            skew
+        };
+
+        assert!(build().simulate_drain_merges() >= depth as usize, "merges the interleaved spines");
+
+        // a cycled item sinks below every item equal to it, here to the end of a long spine
+        let mut shape = Shape::Empty;
+        for _ in 0..depth {
+            shape = Shape::node(0, Shape::Empty, shape);
+        }
+
+        let mut skew = SkewHeap::from_structure(Shape::node(0, Shape::Empty, shape));
+        skew.strategy = MergeStrategy::Iterative;
+
+        assert_eq!(skew.cycle_min(), Some(&0), "cycles the top item");
+        assert_eq!(skew.size(), depth as usize + 1);
+        assert!(skew.is_valid());
+    }
+
+    #[test]
+    fn test_adopt_different_comparator() {
+        let descending = || SkewHeap::new_with_comparator(Box::new(|a: &u32, b: &u32| b.cmp(a)));
+
+        let mut skew = descending();
+        skew.extend(vec![5, 1, 9]);
+        skew.adopt(SkewHeap::heapify(vec![2, 8, 3]));
+        assert!(skew.is_valid());
+        assert_eq!(skew.sorted_snapshot(), vec![9, 8, 5, 3, 2, 1], "adopted items follow this heap's comparator");
+
+        let mut plain = SkewHeap::heapify(vec![5, 1, 9]);
+        let mut other = descending();
+        other.extend(vec![2, 8, 3]);
+        assert_eq!(plain.try_adopt(other), Ok(6));
+        assert_eq!(plain.sorted_snapshot(), vec![1, 2, 3, 5, 8, 9], "comparator heap adopted by a plain heap");
+
+        let mut skew = descending();
+        skew.put(4);
+        let mut shared = skew.empty_like();
+        shared.extend(vec![7, 6]);
+        skew.adopt_all(vec![shared, SkewHeap::heapify(vec![0, 10])]);
+        assert_eq!(skew.sorted_snapshot(), vec![10, 7, 6, 4, 0], "heaps sharing the comparator merge directly");
+    }
+
+    #[test]
+    fn test_adopt_sequenced() {
+        let sequenced = |values: &[char]| {
+            let mut skew = SkewHeap::builder().sequenced().build().unwrap();
+            for &value in values {
+                skew.put(Prioritized::new(1, value));
+            }
+            skew
+        };
+
+        let drain = |mut skew: SkewHeap<Prioritized<u32, char>>| -> String {
+            std::iter::from_fn(|| skew.take()).map(|item| item.value).collect()
+        };
+
+        let mut skew = sequenced(&['a', 'b']);
+        skew.adopt(sequenced(&['c', 'd']));
+        skew.put(Prioritized::new(1, 'e'));
+        assert_eq!(drain(skew), "abcde", "adopted items follow this heap's equal items");
+
+        let mut skew = sequenced(&['a']);
+        skew.adopt_all(vec![sequenced(&['b', 'c']), sequenced(&['d'])]);
+        assert!(skew.is_valid());
+        assert_eq!(skew.size(), 4);
+        let drained = drain(skew);
+        assert!(drained.starts_with('a') && drained.find('b') < drained.find('c'),
+            "each adopted heap keeps its own order: {}", drained);
+
+        let mut plain = SkewHeap::new();
+        for value in "fgh".chars() {
+            plain.put(Prioritized::new(1, value));
         }
+
+        let mut skew = sequenced(&['a']);
+        skew.adopt(plain);
+        assert!(skew.is_valid());
+        assert!(drain(skew).starts_with('a'), "an unsequenced heap's items follow this heap's");
+    }
+
+    #[test]
+    fn test_adopt_none_last() {
+        let mut skew = SkewHeap::new_partial_none_last();
+        skew.extend(vec![2.0, f64::NAN, 1.0]);
+        skew.adopt(SkewHeap::heapify(vec![f64::NAN, 3.0, 0.5]));
+        assert!(skew.is_valid());
+
+        let drained: Vec<f64> = std::iter::from_fn(|| skew.take()).collect();
+        assert_eq!(&drained[..4], &[0.5, 1.0, 2.0, 3.0], "numbers come first");
+        assert!(drained[4..].iter().all(|n| n.is_nan()), "NaN is taken last");
     }
 }
@@ -0,0 +1,301 @@
+//! A double-ended skew heap
+
+use crate::{node_allocated, node_freed, Item};
+
+
+const MIN: usize = 0;
+const MAX: usize = 1;
+
+
+/// A node's position within one of the two heaps
+#[derive(Debug)]
+struct Links<T> {
+    parent: *mut Node<T>,
+    left:   *mut Node<T>,
+    right:  *mut Node<T>,
+}
+
+impl<T> Links<T> {
+    fn new() -> Self {
+        Self {
+            parent: std::ptr::null_mut(),
+            left:   std::ptr::null_mut(),
+            right:  std::ptr::null_mut(),
+        }
+    }
+}
+
+/// Each node is a member of both the min heap and the max heap at once. links[MIN] positions it
+/// within the min heap and links[MAX] within the max heap.
+#[derive(Debug)]
+struct Node<T> {
+    item:  T,
+    links: [Links<T>; 2],
+}
+
+impl<T: Item> Node<T> {
+    fn new(item: T) -> *mut Self {
+        node_allocated();
+        Box::into_raw(Box::new(Self {
+            item,
+            links: [Links::new(), Links::new()],
+        }))
+    }
+
+    /// True if a must be ordered below b in the given side's heap
+    fn after(side: usize, a: &T, b: &T) -> bool {
+        if side == MIN {
+            a > b
+        } else {
+            a < b
+        }
+    }
+
+    /// Merges two trees within one side's heap. The parent link of the returned root is left for
+    /// the caller to set.
+    fn merge(side: usize, a: *mut Self, b: *mut Self) -> *mut Self {
+        if a.is_null() {
+            return b
+        }
+
+        if b.is_null() {
+            return a
+        }
+
+        unsafe{
+            let (a, b) = if Node::after(side, &(*a).item, &(*b).item) { (b, a) } else { (a, b) };
+
+            let new_left_node = Node::merge(side, b, (*a).links[side].right);
+            (*new_left_node).links[side].parent = a;
+
+            (*a).links[side].right = (*a).links[side].left;
+            (*a).links[side].left = new_left_node;
+
+            a
+        }
+    }
+}
+
+
+/// A double-ended priority heap built from two intertwined skew heaps sharing the same nodes: one
+/// ordered by minimum and one by maximum. Every node carries parent links in both heaps, so when
+/// an item is removed from one end its node can be unlinked from the other heap as well, giving
+/// logarithmic amortized time at both ends.
+#[derive(Debug)]
+pub struct MinMaxSkewHeap<T> {
+    count: usize,
+    roots: [*mut Node<T>; 2],
+}
+
+impl<T: Item> MinMaxSkewHeap<T> {
+    /// Returns a new MinMaxSkewHeap
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            roots: [std::ptr::null_mut(), std::ptr::null_mut()],
+        }
+    }
+
+    /// Returns the number of items in the MinMaxSkewHeap
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if there are no items currently in the MinMaxSkewHeap
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.roots[MIN].is_null()
+    }
+
+    /// Inserts an item into the heap and returns the new size
+    pub fn put(&mut self, item: T) -> usize {
+        let node = Node::new(item);
+
+        for side in [MIN, MAX] {
+            self.roots[side] = Node::merge(side, self.roots[side], node);
+            unsafe{ (*self.roots[side]).links[side].parent = std::ptr::null_mut() }
+        }
+
+        self.count += 1;
+        self.count
+    }
+
+    /// Retrieves the smallest item without removing it
+    pub fn peek_min(&self) -> Option<&T> {
+        self.peek(MIN)
+    }
+
+    /// Retrieves the largest item without removing it
+    pub fn peek_max(&self) -> Option<&T> {
+        self.peek(MAX)
+    }
+
+    /// Removes and retrieves the smallest item
+    pub fn take_min(&mut self) -> Option<T> {
+        self.take(MIN)
+    }
+
+    /// Removes and retrieves the largest item
+    pub fn take_max(&mut self) -> Option<T> {
+        self.take(MAX)
+    }
+
+    fn peek(&self, side: usize) -> Option<&T> {
+        if self.is_empty() {
+            return None
+        }
+
+        unsafe{ Some(&(*self.roots[side]).item) }
+    }
+
+    fn take(&mut self, side: usize) -> Option<T> {
+        if self.is_empty() {
+            return None
+        }
+
+        let node = self.roots[side];
+        self.unlink(MIN, node);
+        self.unlink(MAX, node);
+
+        // the node is no longer reachable from either heap, so it may now be freed
+        let node = unsafe{ Box::from_raw(node) };
+        node_freed();
+
+        self.count -= 1;
+        Some(node.item)
+    }
+
+    /// Removes a node from one side's heap by merging its children and splicing the result into
+    /// the node's former position.
+    fn unlink(&mut self, side: usize, node: *mut Node<T>) {
+        unsafe{
+            let links = &(*node).links[side];
+            let parent = links.parent;
+            let subtree = Node::merge(side, links.left, links.right);
+
+            if !subtree.is_null() {
+                (*subtree).links[side].parent = parent;
+            }
+
+            if parent.is_null() {
+                self.roots[side] = subtree;
+            } else if (*parent).links[side].left == node {
+                (*parent).links[side].left = subtree;
+            } else {
+                (*parent).links[side].right = subtree;
+            }
+        }
+    }
+}
+
+impl<T: Item> Default for MinMaxSkewHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MinMaxSkewHeap<T> {
+    fn drop(&mut self) {
+        // Every node is reachable from the min heap alone, so walking it frees each node once
+        if !self.roots[MIN].is_null() {
+            let mut stack = vec![self.roots[MIN]];
+
+            while let Some(node) = stack.pop() {
+                unsafe{
+                    let links = &(*node).links[MIN];
+
+                    if !links.left.is_null() {
+                        stack.push(links.left);
+                    }
+
+                    if !links.right.is_null() {
+                        stack.push(links.right);
+                    }
+
+                    node_freed();
+                    drop(Box::from_raw(node));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MinMaxSkewHeap;
+
+    use rand::rng;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_positive_path() {
+        let mut skew = MinMaxSkewHeap::new();
+
+        assert!(skew.is_empty(), "initially empty");
+        assert_eq!(skew.peek_min(), None, "peek_min returns None when is_empty");
+        assert_eq!(skew.peek_max(), None, "peek_max returns None when is_empty");
+        assert_eq!(skew.take_min(), None, "take_min returns None when is_empty");
+        assert_eq!(skew.take_max(), None, "take_max returns None when is_empty");
+
+        assert_eq!(skew.put(10), 1, "put returns new size");
+        assert_eq!(skew.peek_min(), Some(&10), "single item is the min");
+        assert_eq!(skew.peek_max(), Some(&10), "single item is the max");
+
+        assert_eq!(skew.put(3), 2, "put returns new size");
+        assert_eq!(skew.put(15), 3, "put returns new size");
+        assert_eq!(skew.peek_min(), Some(&3), "peek_min returns smallest");
+        assert_eq!(skew.peek_max(), Some(&15), "peek_max returns largest");
+
+        assert_eq!(skew.take_max(), Some(15), "take_max returns largest");
+        assert_eq!(skew.take_min(), Some(3), "take_min returns smallest");
+        assert_eq!(skew.size(), 1, "size returns expected count after takes");
+        assert_eq!(skew.take_max(), Some(10), "take_max returns final item");
+        assert!(skew.is_empty(), "is_empty after final item taken");
+        assert_eq!(skew.take_min(), None, "take_min returns None once drained");
+    }
+
+    #[test]
+    fn test_alternating_drain() {
+        let size = 1000;
+
+        let mut items: Vec<u32> = (0..size).collect();
+        items.shuffle(&mut rng());
+
+        let mut skew = MinMaxSkewHeap::new();
+        for n in items {
+            skew.put(n);
+        }
+
+        let (mut lo, mut hi) = (0, size - 1);
+
+        while lo <= hi {
+            assert_eq!(skew.peek_min(), Some(&lo));
+            assert_eq!(skew.take_min(), Some(lo));
+            lo += 1;
+
+            if lo > hi {
+                break
+            }
+
+            assert_eq!(skew.peek_max(), Some(&hi));
+            assert_eq!(skew.take_max(), Some(hi));
+            hi -= 1;
+        }
+
+        assert!(skew.is_empty());
+        assert_eq!(skew.size(), 0);
+    }
+
+    #[test]
+    fn test_drop_partially_drained() {
+        let mut skew = MinMaxSkewHeap::new();
+        for n in 0..100 {
+            skew.put(String::from("item") + &n.to_string());
+        }
+
+        skew.take_min();
+        skew.take_max();
+        assert_eq!(skew.size(), 98);
+    }
+}
@@ -0,0 +1,201 @@
+//! An immutable skew heap with structural sharing
+
+use std::rc::Rc;
+
+use crate::Item;
+
+
+type Tree<T> = Option<Rc<Node<T>>>;
+
+#[derive(Debug)]
+struct Node<T> {
+    item:  T,
+    left:  Tree<T>,
+    right: Tree<T>,
+}
+
+impl<T: Item + Clone> Node<T> {
+    /// Merges two trees into a new one. Only the nodes along the merge path are copied; every
+    /// other subtree is shared with the inputs.
+    fn merge(a: &Tree<T>, b: &Tree<T>) -> Tree<T> {
+        match (a, b) {
+            (None, _) => b.clone(),
+            (_, None) => a.clone(),
+            (Some(x), Some(y)) => {
+                let (x, y) = if x.item > y.item { (y, x) } else { (x, y) };
+
+                Some(Rc::new(Node {
+                    item:  x.item.clone(),
+                    left:  Node::merge(&Some(y.clone()), &x.right),
+                    right: x.left.clone(),
+                }))
+            },
+        }
+    }
+}
+
+impl<T> Drop for Node<T> {
+    fn drop(&mut self) {
+        // Unlink children iteratively to avoid recursing down a long chain of uniquely owned nodes
+        let mut stack: Vec<Rc<Node<T>>> = self.left.take().into_iter().chain(self.right.take()).collect();
+
+        while let Some(node) = stack.pop() {
+            if let Ok(mut node) = Rc::try_unwrap(node) {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+            }
+        }
+    }
+}
+
+
+/// An immutable (min) skew heap. Rather than modifying the heap, put and take return a new heap,
+/// leaving the original intact. A skew heap's merge only touches the nodes along its right spine,
+/// so the new heap copies just those nodes and shares every other subtree with the original via
+/// reference counting. Items must implement Clone, since items along the merge path are copied.
+#[derive(Debug)]
+pub struct PersistentSkewHeap<T> {
+    count: usize,
+    root:  Tree<T>,
+}
+
+impl<T: Item + Clone> PersistentSkewHeap<T> {
+    /// Returns a new, empty PersistentSkewHeap
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            root:  None,
+        }
+    }
+
+    /// Returns the number of items in the PersistentSkewHeap
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if there are no items in the PersistentSkewHeap
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Retrieves a reference to the top item without removing it
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.item)
+    }
+
+    /// Returns a new heap containing this heap's items plus item
+    pub fn put(&self, item: T) -> Self {
+        let node = Some(Rc::new(Node { item, left: None, right: None }));
+
+        Self {
+            count: self.count + 1,
+            root:  Node::merge(&self.root, &node),
+        }
+    }
+
+    /// Returns the top item along with a new heap containing the remaining items, or None if the
+    /// heap is empty
+    pub fn take(&self) -> Option<(T, Self)> {
+        let root = self.root.as_ref()?;
+
+        let rest = Self {
+            count: self.count - 1,
+            root:  Node::merge(&root.left, &root.right),
+        };
+
+        Some((root.item.clone(), rest))
+    }
+
+    /// Returns a new heap containing the items of both this heap and other
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            count: self.count + other.count,
+            root:  Node::merge(&self.root, &other.root),
+        }
+    }
+}
+
+impl<T> Clone for PersistentSkewHeap<T> {
+    /// Returns a new handle to the same heap. This is O(1), since the tree is shared.
+    fn clone(&self) -> Self {
+        Self {
+            count: self.count,
+            root:  self.root.clone(),
+        }
+    }
+}
+
+impl<T: Item + Clone> Default for PersistentSkewHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PersistentSkewHeap;
+
+    fn drain(mut heap: PersistentSkewHeap<u32>) -> Vec<u32> {
+        let mut items = vec![];
+
+        while let Some((item, rest)) = heap.take() {
+            items.push(item);
+            heap = rest;
+        }
+
+        items
+    }
+
+    #[test]
+    fn test_positive_path() {
+        let empty = PersistentSkewHeap::new();
+        assert!(empty.is_empty(), "initially empty");
+        assert_eq!(empty.peek(), None, "peek returns None when empty");
+        assert!(empty.take().is_none(), "take returns None when empty");
+
+        let heap = empty.put(10).put(3).put(15);
+        assert_eq!(heap.size(), 3, "size counts every put");
+        assert_eq!(heap.peek(), Some(&3), "peek returns top item");
+        assert!(empty.is_empty(), "put leaves the original heap empty");
+
+        let (item, rest) = heap.take().unwrap();
+        assert_eq!(item, 3, "take returns top item");
+        assert_eq!(rest.size(), 2, "remaining heap has one fewer item");
+        assert_eq!(heap.size(), 3, "take leaves the original heap intact");
+    }
+
+    #[test]
+    fn test_child_does_not_affect_parent() {
+        let mut parent = PersistentSkewHeap::new();
+        for n in [5, 1, 4, 2, 3] {
+            parent = parent.put(n);
+        }
+
+        let child = parent.put(0).put(6);
+        assert_eq!(drain(child), vec![0, 1, 2, 3, 4, 5, 6], "child holds parent's items plus its own");
+        assert_eq!(drain(parent.clone()), vec![1, 2, 3, 4, 5], "parent is unchanged");
+        assert_eq!(parent.size(), 5);
+    }
+
+    #[test]
+    fn test_merge() {
+        let a = PersistentSkewHeap::new().put(1).put(3);
+        let b = PersistentSkewHeap::new().put(2).put(4);
+
+        assert_eq!(drain(a.merge(&b)), vec![1, 2, 3, 4], "merge combines both heaps");
+        assert_eq!(drain(a), vec![1, 3], "merge leaves inputs intact");
+        assert_eq!(drain(b), vec![2, 4], "merge leaves inputs intact");
+    }
+
+    #[test]
+    fn test_drop_large_heap() {
+        let mut heap = PersistentSkewHeap::new();
+        for n in (0..100_000).rev() {
+            heap = heap.put(n);
+        }
+
+        assert_eq!(heap.size(), 100_000);
+    }
+}
@@ -0,0 +1,69 @@
+//! Pairs a payload with a separate priority
+
+use std::cmp::Ordering;
+
+
+/// An item ordered solely by its priority. The value is carried along with the priority but plays
+/// no part in comparisons, which means the value need not implement PartialOrd (or even Clone).
+#[derive(Debug, Clone)]
+pub struct Prioritized<P: PartialOrd + Copy, V> {
+    pub priority: P,
+    pub value:    V,
+}
+
+impl<P: PartialOrd + Copy, V> Prioritized<P, V> {
+    /// Returns a new Prioritized wrapping value at the given priority
+    pub fn new(priority: P, value: V) -> Self {
+        Self { priority, value }
+    }
+
+    /// Consumes the wrapper, returning the priority and value
+    pub fn into_parts(self) -> (P, V) {
+        (self.priority, self.value)
+    }
+}
+
+impl<P: PartialOrd + Copy, V> PartialEq for Prioritized<P, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<P: PartialOrd + Copy, V> PartialOrd for Prioritized<P, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.priority.partial_cmp(&other.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Prioritized;
+    use crate::SkewHeap;
+
+    #[test]
+    fn test_orders_by_priority_only() {
+        assert!(Prioritized::new(1, "z") < Prioritized::new(2, "a"), "value is ignored when ordering");
+        assert!(Prioritized::new(1, "a") == Prioritized::new(1, "b"), "value is ignored when comparing");
+    }
+
+    #[test]
+    fn test_drain_by_priority() {
+        let mut skew = SkewHeap::new();
+
+        for (priority, name) in [(3, "three"), (1, "one"), (4, "four"), (2, "two")] {
+            skew.put(Prioritized::new(priority, String::from(name)));
+        }
+
+        let mut drained = vec![];
+        while let Some(item) = skew.take() {
+            drained.push(item.into_parts());
+        }
+
+        assert_eq!(drained, vec![
+            (1, String::from("one")),
+            (2, String::from("two")),
+            (3, String::from("three")),
+            (4, String::from("four")),
+        ]);
+    }
+}
@@ -0,0 +1,67 @@
+//! Items tagged with the source they came from
+
+use std::cmp::Ordering;
+
+use crate::{Item, SkewHeap};
+
+
+/// An item carrying the id of the source it came from. Only the value takes part in comparisons,
+/// so tagged items are ordered exactly as their values would be.
+#[derive(Debug, Clone)]
+pub struct Tagged<Id, T> {
+    pub id:    Id,
+    pub value: T,
+}
+
+impl<Id, T> Tagged<Id, T> {
+    /// Returns a new Tagged wrapping value with the given source id
+    pub fn new(id: Id, value: T) -> Self {
+        Self { id, value }
+    }
+
+    /// Consumes the wrapper, returning the id and value
+    pub fn into_parts(self) -> (Id, T) {
+        (self.id, self.value)
+    }
+}
+
+impl<Id, T: PartialEq> PartialEq for Tagged<Id, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<Id, T: PartialOrd> PartialOrd for Tagged<Id, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+/// Merges src into dst, tagging each of its items with id, so that once the merged heap is
+/// drained, the source of every item is known. Panics if dst would exceed its capacity.
+pub fn tag_and_adopt<Id: Copy, T: Item>(dst: &mut SkewHeap<Tagged<Id, T>>, src: SkewHeap<T>, id: Id) {
+    dst.adopt_converted(src, |value| Tagged::new(id, value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tag_and_adopt, Tagged};
+    use crate::SkewHeap;
+
+    #[test]
+    fn test_orders_by_value_only() {
+        assert!(Tagged::new('z', 1) < Tagged::new('a', 2), "id is ignored when ordering");
+        assert!(Tagged::new('a', 1) == Tagged::new('b', 1), "id is ignored when comparing");
+    }
+
+    #[test]
+    fn test_provenance() {
+        let mut merged = SkewHeap::new();
+        tag_and_adopt(&mut merged, SkewHeap::heapify(vec![5, 1, 3]), "odd");
+        tag_and_adopt(&mut merged, SkewHeap::heapify(vec![4, 2]), "even");
+
+        let drained: Vec<(&str, u32)> = std::iter::from_fn(|| merged.take()).map(Tagged::into_parts).collect();
+
+        assert_eq!(drained, vec![("odd", 1), ("even", 2), ("odd", 3), ("even", 4), ("odd", 5)]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Streaming selection of the k smallest items
+
+use crate::{BuildError, Item, Order, SkewHeap};
+
+
+/// Retains the k smallest items offered to it from a stream of any length. The items are held in
+/// a bounded max heap, so that the worst of those retained is always at the top, ready to be
+/// evicted by a better item.
+#[derive(Debug)]
+pub struct TopK<T> {
+    heap: SkewHeap<T>,
+}
+
+impl<T: Item> TopK<T> {
+    /// Returns a new TopK retaining at most k items
+    pub fn new(k: usize) -> Result<Self, BuildError> {
+        let heap = SkewHeap::builder().order(Order::Max).capacity(k).build()?;
+        Ok(Self { heap })
+    }
+
+    /// Returns the number of items currently retained
+    pub fn size(&self) -> usize {
+        self.heap.size()
+    }
+
+    /// Retains item if it is among the k smallest offered so far, evicting the worst retained
+    /// item to make room if necessary
+    pub fn offer(&mut self, item: T) {
+        if !self.heap.is_full() {
+            self.heap.put(item);
+            return
+        }
+
+        if self.heap.peek().is_some_and(|worst| item < *worst) {
+            self.heap.take();
+            self.heap.put(item);
+        }
+    }
+
+    /// Consumes the TopK, returning the retained items from smallest to largest
+    pub fn into_sorted(mut self) -> Vec<T> {
+        let mut items: Vec<T> = std::iter::from_fn(|| self.heap.take()).collect();
+        items.reverse();
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopK;
+    use crate::BuildError;
+
+    use rand::rng;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_zero_capacity() {
+        assert_eq!(TopK::<u32>::new(0).err(), Some(BuildError::ZeroCapacity));
+    }
+
+    #[test]
+    fn test_retains_smallest() {
+        let mut items: Vec<u32> = (0..1000).collect();
+        items.shuffle(&mut rng());
+
+        let mut top = TopK::new(10).unwrap();
+        for n in items {
+            top.offer(n);
+        }
+
+        assert_eq!(top.size(), 10, "retains at most k items");
+        assert_eq!(top.into_sorted(), (0..10).collect::<Vec<_>>(), "k smallest items in order");
+    }
+
+    #[test]
+    fn test_fewer_than_k() {
+        let mut top = TopK::new(10).unwrap();
+        for n in [3, 1, 2] {
+            top.offer(n);
+        }
+
+        assert_eq!(top.into_sorted(), vec![1, 2, 3], "retains every item when fewer than k");
+    }
+}
@@ -0,0 +1,114 @@
+//! Leak checks using the process-wide live node counter. These live in their own test binary, and
+//! hold LOCK while running, so that no other test allocates nodes while the counter is compared.
+#![cfg(feature = "track_allocs")]
+
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+use skewheap::{live_node_count, MinMaxSkewHeap, SkewHeap};
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[test]
+fn test_live_node_count() {
+    let _guard = lock();
+    let baseline = live_node_count();
+
+    {
+        let mut a = SkewHeap::heapify((0..100).collect());
+        let mut b = SkewHeap::new();
+        for n in 0..50 {
+            b.put(n);
+        }
+
+        assert_eq!(live_node_count(), baseline + 150, "counts each allocated node");
+
+        a.take();
+        b.take();
+        assert_eq!(live_node_count(), baseline + 148, "taken nodes are freed");
+
+        a.reset_for_reuse();
+        assert_eq!(live_node_count(), baseline + 148, "reset nodes are kept for reuse");
+
+        a.shrink_pool_to(10);
+        assert_eq!(live_node_count(), baseline + 59, "shrinking the pool frees nodes");
+
+        a.put(0);
+        a.adopt(b);
+        assert_eq!(a.dismantle().len(), 50);
+        assert_eq!(live_node_count(), baseline, "dismantle frees every node and the pool");
+
+        let mut minmax = MinMaxSkewHeap::new();
+        for n in 0..10 {
+            minmax.put(n);
+        }
+
+        minmax.take_max();
+        assert_eq!(live_node_count(), baseline + 9, "counts double-ended heap nodes");
+
+        let c = SkewHeap::heapify((0..10).collect::<Vec<u32>>());
+        let _copy = c.clone();
+    }
+
+    assert_eq!(live_node_count(), baseline, "no nodes leak once every heap is dropped");
+}
+
+#[test]
+fn test_freed_on_another_thread() {
+    let _guard = lock();
+    let baseline = live_node_count();
+
+    let skew = SkewHeap::heapify((0..100).collect::<Vec<u32>>());
+    assert_eq!(live_node_count(), baseline + 100, "counts each allocated node");
+
+    thread::spawn(move || drop(skew)).join().unwrap();
+    assert_eq!(live_node_count(), baseline, "nodes freed on another thread are counted");
+}
+
+#[test]
+fn test_into_sorted_stream() {
+    let _guard = lock();
+    let baseline = live_node_count();
+
+    let mut stream = SkewHeap::heapify((0..100).rev().collect::<Vec<u32>>()).into_sorted_stream();
+    assert_eq!(stream.by_ref().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    drop(stream);
+    assert_eq!(live_node_count(), baseline, "every node is freed when the stream is dropped");
+}
+
+#[test]
+fn test_trim_to() {
+    let _guard = lock();
+    let baseline = live_node_count();
+
+    let mut skew = SkewHeap::heapify((0..100).collect::<Vec<u32>>());
+    skew.trim_to(10);
+    assert_eq!(live_node_count(), baseline + 10, "trimmed nodes are freed");
+
+    let mut recycling = SkewHeap::builder().recycling().build().unwrap();
+    recycling.extend(0..100u32);
+    recycling.trim_to(10);
+    assert_eq!(live_node_count(), baseline + 110, "a recycling heap keeps trimmed nodes for reuse");
+
+    drop((skew, recycling));
+    assert_eq!(live_node_count(), baseline);
+}
+
+#[test]
+fn test_bounded_union() {
+    let _guard = lock();
+    let baseline = live_node_count();
+
+    let heaps = (0..4u32).map(|n| SkewHeap::heapify((n * 50..(n + 1) * 50).collect())).collect();
+    let union = SkewHeap::bounded_union(heaps, 5);
+    assert_eq!(union.size(), 5);
+    assert_eq!(live_node_count(), baseline + 5, "discarded nodes are freed");
+
+    drop(union);
+    assert_eq!(live_node_count(), baseline);
+}